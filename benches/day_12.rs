@@ -0,0 +1,27 @@
+/*run the following command in a new terminal to see reports
+cd target\criterion
+python -m http.server
+
+[You might have to change back slashes to forward slashes in target\criterion\report\index.html]
+*/
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use advent_of_code::day_12::terrain::{generate, Terrain};
+use advent_of_code::pathfinding::Graph;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day-12-2000x2000");
+    group.sample_size(10);
+
+    for (name, terrain) in [("ridges", Terrain::Ridges), ("spiral", Terrain::Spiral)] {
+        let map = generate(terrain, 2000, 2000, 12);
+        assert_eq!(map.bfs(map.start, map.end).map(|p| p.cost), map.bidirectional_bfs(map.start, map.end).map(|p| p.cost));
+
+        group.bench_function(format!("{name}-bfs"), |b| b.iter(|| map.bfs(map.start, map.end)));
+        group.bench_function(format!("{name}-bidirectional-bfs"), |b| b.iter(|| map.bidirectional_bfs(map.start, map.end)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
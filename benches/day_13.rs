@@ -0,0 +1,28 @@
+/*run the following command in a new terminal to see reports
+cd target\criterion
+python -m http.server
+
+[You might have to change back slashes to forward slashes in target\criterion\report\index.html]
+*/
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use advent_of_code::day_13::{random_packet, List, Packet, PacketArena};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(13);
+    let lists: Vec<List> = (0..1_000_000).map(|_| random_packet(&mut rng, 4)).collect();
+    let arena: PacketArena = lists.iter().collect();
+
+    let trees: Vec<&List> = lists.iter().collect();
+    let packets: Vec<Packet> = arena.packets().collect();
+
+    let mut group = c.benchmark_group("day-13-sort-1M");
+    group.sample_size(10);
+    group.bench_function("tree", |b| b.iter_batched(|| trees.clone(), |mut v| v.sort(), BatchSize::LargeInput));
+    group.bench_function("arena", |b| b.iter_batched(|| packets.clone(), |mut v| v.sort(), BatchSize::LargeInput));
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -48,4 +48,11 @@ fn main() {
     let next_max = touches.pop().unwrap();
     println!("{} * {} = {}", max, next_max, max * next_max);
 
+    // part 2, one thread per item:
+    let mut touches = parallel::simulate("inputs/11.inputs.txt", 10000);
+    touches.sort();
+    let max = touches.pop().unwrap();
+    let next_max = touches.pop().unwrap();
+    println!("parallel: {} * {} = {}", max, next_max, max * next_max);
+
 }
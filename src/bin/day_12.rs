@@ -1,77 +1,39 @@
 use advent_of_code::day_12::*;
-use std::collections::{HashSet, VecDeque};
+use advent_of_code::day_12::rules::ClimbingRules;
+use advent_of_code::pathfinding::Graph;
+
 fn main() {
 
     // PART 1
 
-    let mut queue: Vec<(usize, usize)> = Vec::new();
-    let mut buf_queue: Vec<(usize, usize)> = Vec::new();
-    let mut tracker: HashSet<(usize, usize)> = HashSet::new();
-    let mut map = ElevationMap::new("inputs/12.inputs.txt");
-    let mut counter: u32 = 1;
-    
-    let (start_idx, end_idx) = get_start_end(&map);
-    map.0[start_idx.0][start_idx.1] = ElevationMap::as_int('a');
-    map.0[end_idx.0][end_idx.1] = ElevationMap::as_int('z');
-
-    push(&mut queue, &mut tracker, start_idx);
+    let map = ElevationMap::new("inputs/12.inputs.txt");
+    let (start_idx, end_idx) = (map.start, map.end);
 
     println!("{start_idx:?}, {end_idx:?}");
-    'main: loop {
-        for coordinate in queue.iter() {
-            for neighbor in neighbors(&map, &coordinate) {
-                if let Some(neighbor) = neighbor {
-                    if neighbor == end_idx {
-                        println!("Hello neighbor");
-                        break 'main;
-                    } else if !tracker.contains(&neighbor) {
-                        push(&mut buf_queue, &mut tracker, neighbor);
-                    }
-                }
-            }
+    match map.route() {
+        Ok(path) => {
+            print!("{}", map.render_path(&path.nodes, PathStyle::Ansi));
+            println!("{}", path.cost);
+        }
+        Err(e) => {
+            print!("{}", e.reachable.render(&map));
+            println!("{e}");
         }
-        queue = buf_queue.clone();
-        buf_queue = Vec::new();
-        counter += 1;
     }
-    println!("{counter}");
-
-    // PART 2: flip start and end, define end by elevation.
-    let mut queue: Vec<(usize, usize)> = Vec::new();
-    let mut buf_queue: Vec<(usize, usize)> = Vec::new();
-    let mut tracker: HashSet<(usize, usize)> = HashSet::new();
-    let mut map = ElevationMap::new("inputs/12.inputs.txt");
-    let mut counter: u32 = 1;
-    
-    let (start_idx, end_idx) = get_start_end(&map);
-    map.0[start_idx.0][start_idx.1] = ElevationMap::as_int('a');
-    map.0[end_idx.0][end_idx.1] = ElevationMap::as_int('z');
 
-    push(&mut queue, &mut tracker, end_idx);
+    // PART 2: measure every cell's distance to E, pick the closest 'a'.
+    let field = distance_field(&map, end_idx);
+    match field.closest(|&(row, column)| map.heights[row][column] == b'a') {
+        Some((best_start, steps)) => println!("{best_start:?}: {steps}"),
+        None => println!("No 'a' can reach E"),
+    }
+    println!("{} cells can't reach E", field.unreachable().len());
 
-    println!("{end_idx:?}");
-    'main: loop {
-        for coordinate in queue.iter() {
-            for neighbor in rev_neighbors(&map, &coordinate) {
-                if let Some(neighbor) = neighbor {
-                    if map.0[neighbor.0][neighbor.1] == ElevationMap::as_int('a') {
-                        println!("Hello neighbor");
-                        break 'main;
-                    } else if !tracker.contains(&neighbor) {
-                        push(&mut buf_queue, &mut tracker, neighbor);
-                    }
-                }
-            }
-        }
-        queue = buf_queue.clone();
-        buf_queue = Vec::new();
-        counter += 1;
+    // VARIANT: no climbing limit, but every unit climbed costs 10 and every unit dropped costs 1.
+    let steep = ClimbingRules { max_ascent: None, ascent_cost: 10, descent_cost: 1, ..Default::default() };
+    match map.with_rules(steep).dijkstra(start_idx, end_idx) {
+        Some(path) => println!("steep: cost {} in {} moves", path.cost, path.steps()),
+        None => println!("steep: E is unreachable from S"),
     }
-    println!("{counter}");
 
 }
-
-fn push(queue: &mut Vec<(usize, usize)>, tracker: &mut HashSet<(usize, usize)>, coordinate: (usize, usize)) {
-    queue.push(coordinate);
-    tracker.insert(coordinate);
-}
\ No newline at end of file
@@ -8,6 +8,8 @@
 /// We learn that this state machine didn't scale at all
 /// 
 /// WHAT A HEADACHE
+///
+/// Both parts now run on the same Rope engine, with 2 and 10 knots.
 /// */
 
 /* 
@@ -18,8 +20,7 @@ React with updated relative position and absolute position
 
 use std::{path::PathBuf, io::{Lines, BufReader, BufRead}, fs::File};
 
-pub use advent_of_code::day_9_structs::*;
-use advent_of_code::day_9_structs::part_2::*;
+pub use advent_of_code::day_9_structs::{RenderOptions, Rope};
 
 fn read_lines(path: PathBuf) -> Lines<BufReader<File>> {
     let file = File::open(path).unwrap();
@@ -29,12 +30,11 @@ fn read_lines(path: PathBuf) -> Lines<BufReader<File>> {
 fn main() {
     let lines = read_lines(PathBuf::from("inputs/9.inputs.txt"));
 
-    let mut state = StateMachine::new();
+    let mut rope = Rope::new(2);
     for line in lines {
-        state.execute_line(line.unwrap());
-
+        rope.step(line.unwrap());
     }
-    println!("part 1: {:?}",state.visited.len());
+    println!("part 1: {}", rope.size());
 
     // PART 2
 
@@ -45,6 +45,16 @@ fn main() {
     }
     println!("part 2: {}", rope.size());
 
+    // `cargo run --bin day_9 -- --frames` replays part 2 and prints one frame per step,
+    // all in the same viewport so they can be played back as an animation.
+    if std::env::args().any(|arg| arg == "--frames") {
+        let options = RenderOptions { bounds: Some(rope.extent()), ..RenderOptions::KNOTS };
+        let mut replay = Rope::new(10);
+        for line in read_lines(PathBuf::from("inputs/9.inputs.txt")) {
+            replay.step_with(line.unwrap(), |rope| println!("{}", rope.render(options)));
+        }
+    }
+
 }
 
 #[cfg(test)]
@@ -61,13 +71,12 @@ D 1
 L 5
 R 2";
 
-        let mut state = StateMachine::new();
+        let mut rope = Rope::new(2);
         let lines = input.lines();
         for line in lines {
-            state.execute_line(line.to_owned());
+            rope.step(line.to_owned());
         }
-        println!("{:?}\n{}",&state.visited,&state.visited.len());
-        assert_eq!(13, state.visited.len());
+        assert_eq!(13, rope.size());
     }
 
     #[test]
@@ -77,12 +86,11 @@ R 1
 U 1
 R 1";
 
-        let mut state = StateMachine::new();
+        let mut rope = Rope::new(2);
         let lines = input.lines();
         for line in lines {
-            state.execute_line(line.to_owned());
+            rope.step(line.to_owned());
         }
-        println!("{:?}\n{}",&state.visited,&state.visited.len());
-        assert_eq!(2, state.visited.len());
+        assert_eq!(2, rope.size());
     }
 }
\ No newline at end of file
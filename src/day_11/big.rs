@@ -1,14 +1,14 @@
 use std::{rc::Rc, cell::RefCell, io::{Lines, BufReader}, fs::File, collections::VecDeque};
 use num::{integer};
 
-#[derive(Debug)]
-enum Operator {
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Operator {
     Plus,
     Times,
 }
 
-#[derive(Debug)]
-enum Operand {
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Operand {
     Old,
     Number(u64)
 }
@@ -16,12 +16,12 @@ enum Operand {
 #[derive(Debug)]
 pub struct PreMonkey {
     id: usize,
-    items: VecDeque<u64>,
-    operator: Operator,       // '*' or '+'
-    operand: Operand, 
-    divisor: u64,
-    true_monkey: usize,
-    false_monkey: usize,
+    pub(super) items: VecDeque<u64>,
+    pub(super) operator: Operator,       // '*' or '+'
+    pub(super) operand: Operand, 
+    pub(super) divisor: u64,
+    pub(super) true_monkey: usize,
+    pub(super) false_monkey: usize,
 }
 
 pub struct Monkey {
@@ -81,13 +81,31 @@ pub fn parse_lines(path: &'static str) -> Vec<Rc<RefCell<Monkey>>> {
     monkeys
 }
 
-fn build_test(divisor: u64) -> Box<dyn Fn(u64) -> bool> {
+/// Runs the sequential engine for `rounds` rounds and returns how many items each monkey examined, by monkey id.
+pub fn simulate(path: &'static str, rounds: usize) -> Vec<u64> {
+    let monkeys = parse_lines(path);
+
+    for _ in 0..rounds {
+        for monkey in monkeys.iter() {
+            let items = monkey.borrow_mut().throw_items();
+            for (item, monkey_id) in items {
+                monkeys[monkey_id].borrow_mut().items.push_back(item);
+            }
+        }
+    }
+
+    monkeys.iter()
+        .map(|monkey| monkey.borrow().examined)
+        .collect()
+}
+
+pub(super) fn build_test(divisor: u64) -> Box<dyn Fn(u64) -> bool> {
     Box::new(
         move |x| x % divisor == 0
     )
 }
 
-fn build_operation(operator: Operator, operand: Operand, lcm: u64) -> Box<dyn Fn(u64) -> u64> {
+pub(super) fn build_operation(operator: Operator, operand: Operand, lcm: u64) -> Box<dyn Fn(u64) -> u64> {
     match operator {
         Operator::Times => match operand {
             Operand::Old => Box::new(move |x| x.pow(2) % lcm),
@@ -100,7 +118,7 @@ fn build_operation(operator: Operator, operand: Operand, lcm: u64) -> Box<dyn Fn
     }
 }
 
-fn lcm_iter(nums: &Vec<u64>) -> u64 {
+pub(super) fn lcm_iter(nums: &Vec<u64>) -> u64 {
     let mut lcm = 1;
     for num in nums {
         lcm = integer::lcm(lcm, *num);
@@ -108,7 +126,7 @@ fn lcm_iter(nums: &Vec<u64>) -> u64 {
     lcm
 }
 
-fn parse_premonkeys(path: &'static str) -> Vec<PreMonkey> {
+pub(super) fn parse_premonkeys(path: &'static str) -> Vec<PreMonkey> {
     let mut premonkeys: Vec<PreMonkey> = Vec::new();
     let mut lines = super::lines(path);
 
@@ -3,6 +3,7 @@
 /// * Double-ended queue (VecDeque)
 /// * Custom ordering with "use std::cmp::Ordering;"
 pub mod big;
+pub mod parallel;
 use std::collections::VecDeque;
 use std::error::Error;
 use std::{rc::Rc, cell::RefCell};
@@ -0,0 +1,110 @@
+/// Key concepts:
+/// * Items never interact with each other. Because every operation is reduced `% lcm`,
+///   an item's path through the monkeys only depends on its own worry level, so each
+///   item can be followed through all the rounds on its own thread.
+/// * `Box<dyn Fn>` isn't `Send`, so each thread rebuilds its closures from plain data.
+use std::sync::Arc;
+use std::thread;
+use super::big::{build_operation, build_test, lcm_iter, parse_premonkeys, Operand, Operator};
+
+/// Everything a thread needs to rebuild a monkey's `operation` and `test` closures.
+#[derive(Debug, Clone, Copy)]
+struct Rule {
+    operator: Operator,
+    operand: Operand,
+    divisor: u64,
+    true_monkey: usize,
+    false_monkey: usize,
+}
+
+/// Same result as `big::simulate`, but every item is simulated concurrently.
+/// Returns how many items each monkey examined, by monkey id.
+pub fn simulate(path: &'static str, rounds: usize) -> Vec<u64> {
+    let premonkeys = parse_premonkeys(path);
+
+    let divisors_lcm = lcm_iter(
+        &premonkeys.iter()
+        .map(|premonkey| premonkey.divisor)
+        .collect::<Vec<u64>>());
+
+    let rules: Arc<Vec<Rule>> = Arc::new(premonkeys.iter()
+        .map(|premonkey| Rule {
+            operator: premonkey.operator,
+            operand: premonkey.operand,
+            divisor: premonkey.divisor,
+            true_monkey: premonkey.true_monkey,
+            false_monkey: premonkey.false_monkey,
+        })
+        .collect());
+
+    let mut handles = Vec::new();
+    for (monkey, premonkey) in premonkeys.iter().enumerate() {
+        for &item in premonkey.items.iter() {
+            let rules = Arc::clone(&rules);
+            handles.push(thread::spawn(move ||
+                follow_item(&rules, divisors_lcm, monkey, item, rounds)));
+        }
+    }
+
+    let mut examined = vec![0; rules.len()];
+    for handle in handles {
+        for (total, count) in examined.iter_mut().zip(handle.join().unwrap()) {
+            *total += count;
+        }
+    }
+    examined
+}
+
+/// Follows a single item for `rounds` rounds, counting every monkey that examines it.
+/// Monkeys take turns in id order, so an item thrown to a monkey with a higher id is
+/// examined again in the same round; otherwise it waits for the next round.
+fn follow_item(rules: &[Rule], lcm: u64, mut monkey: usize, mut item: u64, rounds: usize) -> Vec<u64> {
+    let operations = rules.iter()
+        .map(|rule| build_operation(rule.operator, rule.operand, lcm))
+        .collect::<Vec<_>>();
+    let tests = rules.iter()
+        .map(|rule| build_test(rule.divisor))
+        .collect::<Vec<_>>();
+
+    let mut examined = vec![0; rules.len()];
+    for _ in 0..rounds {
+        loop {
+            examined[monkey] += 1;
+            item = (operations[monkey])(item);
+            let next = match (tests[monkey])(item) {
+                true => rules[monkey].true_monkey,
+                false => rules[monkey].false_monkey,
+            };
+            let same_round = next > monkey;
+            monkey = next;
+            if !same_round {
+                break;
+            }
+        }
+    }
+    examined
+}
+
+#[cfg(test)]
+mod parallel_11_tests {
+    use super::*;
+    use crate::day_11::big;
+
+    #[test]
+    fn matches_big_test() {
+        for rounds in [1, 20, 1000, 10000] {
+            assert_eq!(simulate("inputs/11.inputs.txt", rounds), big::simulate("inputs/11.inputs.txt", rounds));
+        }
+    }
+
+    #[test]
+    fn follow_item_test() {
+        // Monkey 0 throws everything forward to monkey 1, which throws it back.
+        let rules = [
+            Rule { operator: Operator::Plus, operand: Operand::Number(1), divisor: 1, true_monkey: 1, false_monkey: 1 },
+            Rule { operator: Operator::Plus, operand: Operand::Number(1), divisor: 1, true_monkey: 0, false_monkey: 0 },
+        ];
+        assert_eq!(follow_item(&rules, 100, 0, 5, 3), vec![3, 3]);
+        assert_eq!(follow_item(&rules, 100, 1, 5, 3), vec![2, 3]);
+    }
+}
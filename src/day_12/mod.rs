@@ -1,6 +1,11 @@
-use super::{BufReader, File};
+pub mod rules;
+pub mod terrain;
+
+use super::pathfinding::{bidirectional_bfs, distances, manhattan, Graph, Path};
+use rules::ClimbingRules;
 use std::fmt::Display;
-use std::collections::{HashSet, VecDeque};
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
 /// Key conecpts:
 /// * Breadth-first search. Inspo: https://youtu.be/umszOeerdsU
@@ -9,26 +14,93 @@ use std::collections::{HashSet, VecDeque};
 ///   - http://featherweightmusings.blogspot.com/2015/04/graphs-in-rust.html
 ///   - https://github.com/nrc/r4cppp/blob/master/graphs/README.md
 ///   - In reality, we won't be saving any sort of tree structure to memory. We will just be traversing the map as if it were a tree.
-/// - utf8 encodings
-/// - static variables and the //unsafe block//
-/// - Breaking out of nested loops with loop labels
+/// * Parsing into a typed enum with TryFrom and FromStr
+///
 /// Baby's first tree with Box<> smart pointers.
 /// 
 /// We are building a tree. We will construct this tree by starting at start and adding on each new available step as a node.
 /// We will never add the same coordinate to two different nodes, because the second node would represent a longer path
 ///     than the first node (assuming breadth-first search). We can keep track of that by tracking the coordinates of squares added.
 /// 
-/// We will compare height by mapping letters to integer values by encoding to utf8.
+/// We compare heights by their ASCII byte, so 'a' (97) is the lowest and 'z' (122) the highest.
+/// `S` and `E` are parsed as their own cells and stored as 'a' and 'z'.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Start,
+    End,
+    Height(u8),
+}
 
-static mut CHAR_BYTES: [u8; 1] = [0;1];
+impl Cell {
+    /// Elevation as the ASCII byte of its letter. `S` sits at 'a', `E` at 'z'.
+    pub fn elevation(&self) -> u8 {
+        match self {
+            Self::Start => b'a',
+            Self::End => b'z',
+            Self::Height(h) => *h,
+        }
+    }
+}
+
+impl TryFrom<char> for Cell {
+    type Error = InvalidCellError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'S' => Ok(Self::Start),
+            'E' => Ok(Self::End),
+            'a'..='z' => Ok(Self::Height(value as u8)),
+            _ => Err(InvalidCellError(value)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidCellError(char);
+impl std::error::Error for InvalidCellError {}
+impl Display for InvalidCellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cannot parse char {:?} as elevation", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseMapError {
+    InvalidCell { row: usize, column: usize, source: InvalidCellError },
+    RaggedRow(usize),
+    MissingStart,
+    MissingEnd,
+    DuplicateStart((usize, usize)),
+    DuplicateEnd((usize, usize)),
+}
+impl std::error::Error for ParseMapError {}
+impl Display for ParseMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCell { row, column, source } => write!(f, "{source} at {row}.{column}"),
+            Self::RaggedRow(row) => write!(f, "row {row} has a different length than row 0"),
+            Self::MissingStart => write!(f, "map has no start (S)"),
+            Self::MissingEnd => write!(f, "map has no end (E)"),
+            Self::DuplicateStart((row, column)) => write!(f, "second start (S) at {row}.{column}"),
+            Self::DuplicateEnd((row, column)) => write!(f, "second end (E) at {row}.{column}"),
+        }
+    }
+}
 
-#[derive(Debug)]
-pub struct ElevationMap (pub Vec<Vec<u8>>);
+/// Heights of every cell (`S` and `E` already replaced with 'a' and 'z') plus where `S` and `E` were.
+/// Holds no shared or global state, so it is `Send + Sync` and can be shared between search threads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElevationMap {
+    pub heights: Vec<Vec<u8>>,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
 
 impl Display for ElevationMap {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut s = String::new();
-        for row in &self.0 {
+        for row in &self.heights {
             for column in row {
                 s.push_str(format!("{:03} ", column).as_str())
             }
@@ -38,119 +110,310 @@ impl Display for ElevationMap {
     }
 }
 
+impl FromStr for ElevationMap {
+    type Err = ParseMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut heights: Vec<Vec<u8>> = Vec::new();
+        let mut start: Option<(usize, usize)> = None;
+        let mut end: Option<(usize, usize)> = None;
+
+        for (row, line) in s.lines().enumerate() {
+            let mut v: Vec<u8> = Vec::new();
+            for (column, c) in line.chars().enumerate() {
+                let cell = Cell::try_from(c)
+                    .map_err(|source| ParseMapError::InvalidCell { row, column, source })?;
+                match cell {
+                    Cell::Start if start.is_some() => return Err(ParseMapError::DuplicateStart((row, column))),
+                    Cell::End if end.is_some() => return Err(ParseMapError::DuplicateEnd((row, column))),
+                    Cell::Start => start = Some((row, column)),
+                    Cell::End => end = Some((row, column)),
+                    Cell::Height(_) => (),
+                }
+                v.push(cell.elevation());
+            }
+            if !heights.is_empty() && v.len() != heights[0].len() {
+                return Err(ParseMapError::RaggedRow(row));
+            }
+            heights.push(v);
+        }
+
+        Ok(Self {
+            heights,
+            start: start.ok_or(ParseMapError::MissingStart)?,
+            end: end.ok_or(ParseMapError::MissingEnd)?,
+        })
+    }
+}
+
+/// How `ElevationMap::render_path` draws the route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStyle {
+    /// Same text as `Display`, with arrows on the path.
+    Plain,
+    /// Arrows highlighted with ANSI escape codes, for printing to a terminal.
+    Ansi,
+}
+
+const ANSI_PATH: &str = "\x1b[1;31m";
+const ANSI_ENDPOINT: &str = "\x1b[1;32m";
+const ANSI_RESET: &str = "\x1b[0m";
+
 impl ElevationMap{
     pub fn new(path: &'static str) -> Self {
-        let lines = super::lines(path);
-        let map = lines.map(
-            |line| line.unwrap().chars().map(
-                |c| Self::as_int(c)
-            ).collect::<Vec<u8>>()
-        ).collect::<Vec<Vec<u8>>>();
-        Self(map)
+        std::fs::read_to_string(path).unwrap()
+            .parse()
+            .unwrap()
     }
 
-    pub fn as_int(c: char) -> u8 {
-        unsafe { 
-            c.encode_utf8(&mut CHAR_BYTES); 
-            CHAR_BYTES[0]
+    pub fn height(&self, &(row, column): &(usize, usize)) -> u8 {
+        self.heights[row][column]
+    }
+
+    /// The cell as it was in the input: S and E are told apart from plain 'a' and 'z'.
+    pub fn cell(&self, position: &(usize, usize)) -> Cell {
+        if *position == self.start {
+            Cell::Start
+        } else if *position == self.end {
+            Cell::End
+        } else {
+            Cell::Height(self.height(position))
         }
     }
-}
 
-pub fn get_start_end(map: &ElevationMap) -> ((usize, usize), (usize, usize)) {
-    let mut start: (usize, usize) = (0,0);
-    let mut end: (usize, usize) = (0,0);
-    for i in 0_usize..map.0.len() {
-        for j in 0_usize..map.0[0].len() {
-            if map.0[i][j] == 83 {
-                println!("Start: {i}.{j}");
-                start = (i, j)
-            } else if map.0[i][j] == 69 {
-                println!("End: {i}.{j}");
-                end = (i, j);
+    pub fn rows(&self) -> usize {
+        self.heights.len()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.heights.first().map_or(0, |row| row.len())
+    }
+
+    /// Renders the map like `Display`, but every cell on `path` shows the direction of the
+    /// next move (`^ v < >`, `\` or `/` for diagonals). The last cell shows `S` or `E` if it
+    /// is one, and `*` for any other goal.
+    pub fn render_path(&self, path: &[(usize, usize)], style: PathStyle) -> String {
+        let mut overlay: Vec<Vec<Option<char>>> = self.heights.iter()
+            .map(|row| vec![None; row.len()])
+            .collect();
+        for step in path.windows(2) {
+            overlay[step[0].0][step[0].1] = Some(direction(&step[0], &step[1]));
+        }
+        if let Some(&(row, column)) = path.last() {
+            overlay[row][column] = Some(match self.cell(&(row, column)) {
+                Cell::Start => 'S',
+                Cell::End => 'E',
+                Cell::Height(_) => '*',
+            });
+        }
+
+        let mut s = String::new();
+        for (i, row) in self.heights.iter().enumerate() {
+            for (j, column) in row.iter().enumerate() {
+                match (overlay[i][j], style) {
+                    (None, _) => s.push_str(format!("{:03} ", column).as_str()),
+                    (Some(c), PathStyle::Plain) => s.push_str(format!(" {c}  ").as_str()),
+                    (Some(c), PathStyle::Ansi) => {
+                        let endpoint = (i, j) == path[0] || Some(&(i, j)) == path.last();
+                        let color = if endpoint { ANSI_ENDPOINT } else { ANSI_PATH };
+                        s.push_str(format!("{color} {c} {ANSI_RESET} ").as_str())
+                    }
+                }
             }
+            s.push('\n');
         }
+        s
     }
-    (start, end)
-}
 
-pub fn neighbors(map: &ElevationMap, coordinate: &(usize, usize)) -> [Option<(usize, usize)>; 4] {
-    let mut valid_neighbors: [Option<(usize, usize)>; 4] = [None; 4];      // initialize a coordinates buffer.
+    /// Puzzle-rule BFS from both `start` and `goal` at once. Same cost as `Graph::bfs`, but
+    /// explores much less of large maps.
+    pub fn bidirectional_bfs(&self, start: (usize, usize), goal: (usize, usize)) -> Option<Path<(usize, usize)>> {
+        bidirectional_bfs(
+            start,
+            goal,
+            |coordinate| neighbors(self, coordinate).into_iter().flatten(),
+            |coordinate| rev_neighbors(self, coordinate).into_iter().flatten(),
+        )
+    }
 
-    let &(row, column) = coordinate;
-    let mut elevation = map.0[row][column];
-    if elevation == 69 {
-        elevation = ElevationMap::as_int('z');
-    } else if elevation == 83 {
-        elevation = ElevationMap::as_int('a');
+    /// Shortest puzzle-rule route from `S` to `E`. When there is none, the error says which
+    /// part of the map `S` can actually get to.
+    pub fn route(&self) -> Result<Path<(usize, usize)>, Unreachable> {
+        self.bfs(self.start, self.end).ok_or_else(|| Unreachable {
+            start: self.start,
+            end: self.end,
+            reachable: self.reachable_from(self.start),
+        })
     }
 
-    if row > 0 {
-        if test_cell(map.0[row - 1][column], elevation) {
-            valid_neighbors[0] = Some((row - 1, column));
+    /// Every cell that can be reached from `start` under the puzzle's rule.
+    pub fn reachable_from(&self, start: (usize, usize)) -> Region {
+        let mut region = Region(vec![vec![false; self.columns()]; self.rows()]);
+        for (row, column) in distances(start, |coordinate| self.neighbors(coordinate)).into_keys() {
+            region.0[row][column] = true;
         }
+        region
     }
-    if row < map.0.len() - 1 {
-        if test_cell(map.0[row + 1][column], elevation) {
-            valid_neighbors[1] = Some((row + 1, column));
-        }
+
+    /// ASCII byte of an elevation letter.
+    pub fn as_int(c: char) -> u8 {
+        u8::try_from(c).expect("elevations are ASCII letters")
     }
-    if column > 0 {
-        if test_cell(map.0[row][column - 1], elevation) {
-            valid_neighbors[2] = Some((row, column - 1));
-        }
+}
+
+/// Searches use the forward climbing rule.
+impl Graph for ElevationMap {
+    type Node = (usize, usize);
+
+    fn neighbors(&self, node: &(usize, usize)) -> Vec<(usize, usize)> {
+        neighbors(self, node).into_iter().flatten().collect()
+    }
+
+    fn heuristic(&self, from: &(usize, usize), goal: &(usize, usize)) -> u32 {
+        manhattan(from, goal)
     }
-    if column < map.0[0].len() - 1 {
-        if test_cell(map.0[row][column + 1], elevation) {
-            valid_neighbors[3] = Some((row, column + 1));
+}
+
+/// A set of cells on an `ElevationMap`, stored as one flag per cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region(pub Vec<Vec<bool>>);
+
+impl Region {
+    pub fn contains(&self, &(row, column): &(usize, usize)) -> bool {
+        self.0[row][column]
+    }
+
+    /// Number of cells in the region.
+    pub fn size(&self) -> usize {
+        self.0.iter().flatten().filter(|&&inside| inside).count()
+    }
+
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        self.0.iter().enumerate()
+            .flat_map(|(i, row)| row.iter().enumerate().filter(|(_, &inside)| inside).map(move |(j, _)| (i, j)))
+            .collect()
+    }
+
+    /// Highest elevation inside the region, as a letter.
+    pub fn summit(&self, map: &ElevationMap) -> Option<char> {
+        self.cells().iter().map(|cell| map.height(cell)).max().map(char::from)
+    }
+
+    /// The region as text: the elevation letter for cells inside it, `.` for the rest.
+    pub fn render(&self, map: &ElevationMap) -> String {
+        let mut s = String::new();
+        for (i, row) in self.0.iter().enumerate() {
+            for (j, &inside) in row.iter().enumerate() {
+                s.push(if inside { char::from(map.heights[i][j]) } else { '.' });
+            }
+            s.push('\n');
         }
+        s
     }
+}
 
-    valid_neighbors
+/// No route from `S` to `E`. `reachable` is everything `S` can get to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unreachable {
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    pub reachable: Region,
+}
+impl std::error::Error for Unreachable {}
+impl Display for Unreachable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cells = self.reachable.0.len() * self.reachable.0.first().map_or(0, |row| row.len());
+        write!(f, "E at {}.{} is unreachable from S at {}.{} (S can reach {} of {} cells)",
+            self.end.0, self.end.1, self.start.0, self.start.1, self.reachable.size(), cells)
+    }
 }
 
-pub fn rev_neighbors(map: &ElevationMap, coordinate: &(usize, usize)) -> [Option<(usize, usize)>; 4] {
-    let mut valid_neighbors: [Option<(usize, usize)>; 4] = [None; 4];      // initialize a coordinates buffer.
+/// Number of moves from every cell to one target cell (usually `E`), or `None` if the
+/// cell can't reach it. Built with a single reverse BFS, so any number of questions about
+/// the map can be answered without searching again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceField(pub Vec<Vec<Option<u32>>>);
 
-    let &(row, column) = coordinate;
-    let mut elevation = map.0[row][column];
-    if elevation == 69 {
-        elevation = ElevationMap::as_int('z');
-    } else if elevation == 83 {
-        elevation = ElevationMap::as_int('a');
+impl DistanceField {
+    pub fn get(&self, &(row, column): &(usize, usize)) -> Option<u32> {
+        self.0[row][column]
     }
 
-    if row > 0 {
-        if rev_test_cell(map.0[row - 1][column], elevation) {
-            valid_neighbors[0] = Some((row - 1, column));
-        }
+    /// The cell closest to the target among those matching `predicate`, e.g. the best
+    /// starting `'a'` for part 2.
+    pub fn closest<P: FnMut(&(usize, usize)) -> bool>(&self, mut predicate: P) -> Option<((usize, usize), u32)> {
+        self.cells()
+            .filter_map(|(cell, distance)| Some((cell, distance?)))
+            .filter(|(cell, _)| predicate(cell))
+            .min_by_key(|&(_, distance)| distance)
     }
-    if row < map.0.len() - 1 {
-        if rev_test_cell(map.0[row + 1][column], elevation) {
-            valid_neighbors[1] = Some((row + 1, column));
-        }
+
+    /// Cells from which the target can't be reached at all.
+    pub fn unreachable(&self) -> Vec<(usize, usize)> {
+        self.cells()
+            .filter(|(_, distance)| distance.is_none())
+            .map(|(cell, _)| cell)
+            .collect()
     }
-    if column > 0 {
-        if rev_test_cell(map.0[row][column - 1], elevation) {
-            valid_neighbors[2] = Some((row, column - 1));
+
+    /// How many cells sit at each distance from the target. Unreachable cells are left out.
+    pub fn histogram(&self) -> BTreeMap<u32, usize> {
+        let mut histogram = BTreeMap::new();
+        for distance in self.0.iter().flatten().flatten() {
+            *histogram.entry(*distance).or_insert(0) += 1;
         }
+        histogram
     }
-    if column < map.0[0].len() - 1 {
-        if rev_test_cell(map.0[row][column + 1], elevation) {
-            valid_neighbors[3] = Some((row, column + 1));
-        }
+
+    fn cells(&self) -> impl Iterator<Item = ((usize, usize), Option<u32>)> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, row)| {
+            row.iter().enumerate().map(move |(j, distance)| ((i, j), *distance))
+        })
     }
+}
 
-    valid_neighbors
+/// Distance from every cell to `target`, walking the climbing rule backwards with `rev_neighbors`.
+pub fn distance_field(map: &ElevationMap, target: (usize, usize)) -> DistanceField {
+    let mut field = DistanceField(map.heights.iter()
+        .map(|row| vec![None; row.len()])
+        .collect());
+    let found = distances(target, |coordinate| rev_neighbors(map, coordinate).into_iter().flatten());
+    for ((row, column), distance) in found {
+        field.0[row][column] = Some(distance);
+    }
+    field
 }
 
+/// Cells one puzzle-rule move away from `coordinate` (up, down, left, right).
+pub fn neighbors(map: &ElevationMap, coordinate: &(usize, usize)) -> [Option<(usize, usize)>; 4] {
+    fixed(map.moves(&ClimbingRules::PUZZLE, coordinate))
+}
 
-fn test_cell(neighbor: u8, current: u8) -> bool {
-    neighbor <= current + 1
+/// Cells that can reach `coordinate` in one puzzle-rule move.
+pub fn rev_neighbors(map: &ElevationMap, coordinate: &(usize, usize)) -> [Option<(usize, usize)>; 4] {
+    fixed(map.rev_moves(&ClimbingRules::PUZZLE, coordinate))
 }
 
-fn rev_test_cell(neighbor: u8, current: u8) -> bool {
-    current <= neighbor + 1
+/// Packs 4-way moves into the fixed-size buffer `neighbors` and `rev_neighbors` return.
+fn fixed(moves: Vec<((usize, usize), u32)>) -> [Option<(usize, usize)>; 4] {
+    let mut valid_neighbors: [Option<(usize, usize)>; 4] = [None; 4];
+    for (slot, (next, _)) in valid_neighbors.iter_mut().zip(moves) {
+        *slot = Some(next);
+    }
+    valid_neighbors
+}
+
+/// Arrow for a single move between two adjacent cells. Diagonal moves are drawn as `\` or `/`.
+fn direction(from: &(usize, usize), to: &(usize, usize)) -> char {
+    match (to.0 as isize - from.0 as isize, to.1 as isize - from.1 as isize) {
+        (-1, 0) => '^',
+        (1, 0) => 'v',
+        (0, -1) => '<',
+        (0, 1) => '>',
+        (-1, -1) | (1, 1) => '\\',
+        (-1, 1) | (1, -1) => '/',
+        _ => '?',
+    }
 }
 
 #[cfg(test)]
@@ -162,10 +425,149 @@ mod day_12_tests {
         assert_eq!(ElevationMap::as_int('z'), 122);
     }
 
+    #[test]
+    fn test_cell() {
+        assert_eq!(Cell::try_from('S'), Ok(Cell::Start));
+        assert_eq!(Cell::try_from('E'), Ok(Cell::End));
+        assert_eq!(Cell::try_from('m'), Ok(Cell::Height(b'm')));
+        assert_eq!(Cell::try_from('#'), Err(InvalidCellError('#')));
+        assert_eq!(Cell::try_from('é'), Err(InvalidCellError('é')));
+        assert_eq!(Cell::Start.elevation(), b'a');
+        assert_eq!(Cell::End.elevation(), b'z');
+    }
+
     #[test]
     fn test_new_map() {
         let map = ElevationMap::new("inputs/12.inputs.txt");
         println!("{map}");
+        assert_eq!(map.start, (20, 0));
+        assert_eq!(map.end, (20, 137));
+        assert_eq!(map.height(&map.start), b'a');
+        assert_eq!(map.height(&map.end), b'z');
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let map: ElevationMap = "Sab\ncEz".parse().unwrap();
+        assert_eq!(map.heights, vec![vec![97, 97, 98], vec![99, 122, 122]]);
+        assert_eq!((map.start, map.end), ((0, 0), (1, 1)));
+
+        assert_eq!("Sa\nb1E".parse::<ElevationMap>(), Err(ParseMapError::InvalidCell { row: 1, column: 1, source: InvalidCellError('1') }));
+        assert_eq!("Sab\nEa".parse::<ElevationMap>(), Err(ParseMapError::RaggedRow(1)));
+        assert_eq!("aaE".parse::<ElevationMap>(), Err(ParseMapError::MissingStart));
+        assert_eq!("aaS".parse::<ElevationMap>(), Err(ParseMapError::MissingEnd));
+        assert_eq!("SaS\nEaa".parse::<ElevationMap>(), Err(ParseMapError::DuplicateStart((0, 2))));
+        assert_eq!("SEE".parse::<ElevationMap>(), Err(ParseMapError::DuplicateEnd((0, 2))));
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let map = std::sync::Arc::new(ElevationMap::new("inputs/12.inputs.txt"));
+        let handles = [map.start, (0, 0), (40, 0)].map(|start| {
+            let map = std::sync::Arc::clone(&map);
+            std::thread::spawn(move || map.bfs(start, map.end).map(|path| path.cost))
+        });
+        let costs = handles.map(|handle| handle.join().unwrap());
+        assert_eq!(costs[0], Some(437));
+        assert!(costs.iter().all(|cost| cost.is_some()));
+    }
+
+    #[test]
+    fn test_searches_agree() {
+        let map = ElevationMap::new("inputs/12.inputs.txt");
+        let (start, end) = (map.start, map.end);
+
+        let bfs = map.bfs(start, end).unwrap();
+        let bidirectional = map.bidirectional_bfs(start, end).unwrap();
+        let dijkstra = map.dijkstra(start, end).unwrap();
+        let astar = map.astar(start, end).unwrap();
+        assert_eq!(bfs.cost, 437);
+        assert_eq!(bidirectional.cost, 437);
+        assert_eq!(bidirectional.nodes[0], start);
+        assert_eq!(bidirectional.nodes[437], end);
+        assert_eq!(dijkstra.cost, 437);
+        assert_eq!(astar.cost, 437);
+        assert_eq!(astar.steps(), 437);
+        assert_eq!(astar.nodes[0], start);
+        assert_eq!(astar.nodes[437], end);
+    }
+
+    #[test]
+    fn test_distance_field() {
+        let map = ElevationMap::new("inputs/12.inputs.txt");
+        let (start, end) = (map.start, map.end);
+
+        let field = distance_field(&map, end);
+        assert_eq!(field.get(&start), Some(437));
+        assert_eq!(field.get(&end), Some(0));
+
+        let (_, steps) = field.closest(|&(row, column)| map.heights[row][column] == b'a').unwrap();
+        assert_eq!(steps, 430);
+
+        let histogram = field.histogram();
+        assert_eq!(histogram[&0], 1);
+        let cells = map.rows() * map.columns();
+        assert_eq!(histogram.values().sum::<usize>() + field.unreachable().len(), cells);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        // The 'a' can't climb onto the 'e'. The 'e' can drop down to the 'b' and climb to the 'c'.
+        let map = ElevationMap { heights: vec![vec![97, 101, 98, 99]], start: (0, 0), end: (0, 3) };
+        let field = distance_field(&map, map.end);
+        assert_eq!(field.0, vec![vec![None, Some(2), Some(1), Some(0)]]);
+        assert_eq!(field.unreachable(), vec![(0, 0)]);
+        assert_eq!(field.closest(|_| true), Some(((0, 3), 0)));
+        assert_eq!(field.closest(|&cell| cell != (0, 3)), Some(((0, 2), 1)));
+        assert_eq!(field.histogram(), BTreeMap::from([(0, 1), (1, 1), (2, 1)]));
+    }
+
+    #[test]
+    fn test_route() {
+        let map = ElevationMap::new("inputs/12.inputs.txt");
+        assert_eq!(map.route().unwrap().cost, 437);
+        assert!(map.reachable_from(map.start).contains(&map.end));
+
+        // The 'c' wall can't be climbed from the 'a's, so S only reaches the left two columns.
+        let map: ElevationMap = "Sacz\naacE\naacz".parse().unwrap();
+        let error = map.route().unwrap_err();
+        assert_eq!(error.reachable.size(), 6);
+        assert_eq!(error.reachable.cells(), vec![(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)]);
+        assert!(!error.reachable.contains(&map.end));
+        assert_eq!(error.reachable.summit(&map), Some('a'));
+        assert_eq!(error.reachable.render(&map), "aa..\naa..\naa..\n");
+        assert_eq!(error.to_string(), "E at 1.3 is unreachable from S at 0.0 (S can reach 6 of 12 cells)");
+    }
+
+    #[test]
+    fn test_render_path() {
+        let map: ElevationMap = "Sbc\nfEd".parse().unwrap();
+        let path = [(0, 0), (0, 1), (0, 2), (1, 2), (1, 1)];
+        assert_eq!(map.to_string(), "097 098 099 \n102 122 100 \n");
+        assert_eq!(map.render_path(&path, PathStyle::Plain), " >   >   v  \n102  E   <  \n");
+        assert_eq!(map.render_path(&[], PathStyle::Plain), map.to_string());
+
+        let ansi = map.render_path(&path, PathStyle::Ansi);
+        assert!(ansi.starts_with("\x1b[1;32m > \x1b[0m \x1b[1;31m > \x1b[0m "));
+        assert!(ansi.contains("102 \x1b[1;32m E \x1b[0m "));
+    }
+
+    #[test]
+    fn test_render_path_other_goals() {
+        let map: ElevationMap = "Sbc\nfEd".parse().unwrap();
+        assert_eq!(map.cell(&(0, 0)), Cell::Start);
+        assert_eq!(map.cell(&(1, 1)), Cell::End);
+        assert_eq!(map.cell(&(0, 2)), Cell::Height(b'c'));
+
+        // To some other cell: no E anywhere, the goal is *.
+        let path = [(0, 0), (0, 1), (0, 2)];
+        assert_eq!(map.render_path(&path, PathStyle::Plain), " >   >   *  \n102 122 100 \n");
+        let ansi = map.render_path(&path, PathStyle::Ansi);
+        assert!(ansi.contains("\x1b[1;32m * \x1b[0m "));
+
+        // The route reversed, from E back to S.
+        let path = [(1, 1), (1, 2), (0, 2), (0, 1), (0, 0)];
+        assert_eq!(map.render_path(&path, PathStyle::Plain), " S   <   <  \n102  >   ^  \n");
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,222 @@
+/// Key concepts:
+/// * Plain-data configuration structs with `Default` and struct update syntax (`..Default::default()`)
+/// * A wrapper type (`RuledMap`) to give one map several different `Graph` impls
+use super::ElevationMap;
+use crate::pathfinding::{manhattan, Graph};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    /// Up, down, left and right.
+    FourWay,
+    /// Diagonals too.
+    EightWay,
+}
+
+/// Which moves between cells are allowed and how much each one costs.
+/// Every move costs `flat_cost`, plus `ascent_cost` per unit climbed or `descent_cost` per unit dropped.
+/// The default is the puzzle's rule: climb at most 1, drop any amount, 4-way moves, every move costs 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClimbingRules {
+    pub max_ascent: Option<u8>,     // None = no limit
+    pub max_descent: Option<u8>,    // None = no limit
+    pub movement: Movement,
+    pub flat_cost: u32,
+    pub ascent_cost: u32,
+    pub descent_cost: u32,
+}
+
+impl ClimbingRules {
+    pub const PUZZLE: Self = Self {
+        max_ascent: Some(1),
+        max_descent: None,
+        movement: Movement::FourWay,
+        flat_cost: 1,
+        ascent_cost: 0,
+        descent_cost: 0,
+    };
+
+    /// Whether a single move from elevation `from` to elevation `to` is allowed.
+    pub fn allows(&self, from: u8, to: u8) -> bool {
+        if to >= from {
+            self.max_ascent.is_none_or(|max| to - from <= max)
+        } else {
+            self.max_descent.is_none_or(|max| from - to <= max)
+        }
+    }
+
+    pub fn cost(&self, from: u8, to: u8) -> u32 {
+        if to >= from {
+            self.flat_cost + self.ascent_cost * (to - from) as u32
+        } else {
+            self.flat_cost + self.descent_cost * (from - to) as u32
+        }
+    }
+
+    /// Rules for walking a route backwards (from the goal towards the start): ascents become descents.
+    /// `allows(a, b)` on the reversed rules is `allows(b, a)` on the originals, and the same for `cost`.
+    pub fn reversed(&self) -> Self {
+        Self {
+            max_ascent: self.max_descent,
+            max_descent: self.max_ascent,
+            ascent_cost: self.descent_cost,
+            descent_cost: self.ascent_cost,
+            ..*self
+        }
+    }
+
+    /// Lower bound on the cost of going from `from` to `goal`, for A*.
+    fn heuristic(&self, from: &(usize, usize), goal: &(usize, usize)) -> u32 {
+        let moves = match self.movement {
+            Movement::FourWay => manhattan(from, goal),
+            Movement::EightWay => from.0.abs_diff(goal.0).max(from.1.abs_diff(goal.1)) as u32,
+        };
+        moves * self.flat_cost
+    }
+}
+
+impl Default for ClimbingRules {
+    fn default() -> Self {
+        Self::PUZZLE
+    }
+}
+
+impl ElevationMap {
+    /// Cells next to `coordinate` (4 or 8 of them, depending on `movement`) that are inside the map.
+    pub fn adjacent(&self, movement: Movement, &(row, column): &(usize, usize)) -> Vec<(usize, usize)> {
+        let offsets: &[(isize, isize)] = match movement {
+            Movement::FourWay => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Movement::EightWay => &[(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)],
+        };
+        offsets.iter()
+            .filter_map(|&(dr, dc)| Some((row.checked_add_signed(dr)?, column.checked_add_signed(dc)?)))
+            .filter(|&(r, c)| r < self.rows() && c < self.columns())
+            .collect()
+    }
+
+    /// Every cell reachable from `coordinate` in one move, with the cost of that move.
+    pub fn moves(&self, rules: &ClimbingRules, coordinate: &(usize, usize)) -> Vec<((usize, usize), u32)> {
+        let elevation = self.height(coordinate);
+        self.adjacent(rules.movement, coordinate)
+            .into_iter()
+            .filter(|next| rules.allows(elevation, self.height(next)))
+            .map(|next| (next, rules.cost(elevation, self.height(&next))))
+            .collect()
+    }
+
+    /// Every cell that can reach `coordinate` in one move, with the cost of that move.
+    pub fn rev_moves(&self, rules: &ClimbingRules, coordinate: &(usize, usize)) -> Vec<((usize, usize), u32)> {
+        self.moves(&rules.reversed(), coordinate)
+    }
+
+    /// View of the map that searches with `rules` instead of the puzzle's rule.
+    pub fn with_rules(&self, rules: ClimbingRules) -> RuledMap<'_> {
+        RuledMap { map: self, rules }
+    }
+}
+
+pub struct RuledMap<'a> {
+    pub map: &'a ElevationMap,
+    pub rules: ClimbingRules,
+}
+
+impl Graph for RuledMap<'_> {
+    type Node = (usize, usize);
+
+    fn neighbors(&self, node: &(usize, usize)) -> Vec<(usize, usize)> {
+        self.map.moves(&self.rules, node)
+            .into_iter()
+            .map(|(next, _)| next)
+            .collect()
+    }
+
+    fn cost(&self, from: &(usize, usize), to: &(usize, usize)) -> u32 {
+        self.rules.cost(self.map.height(from), self.map.height(to))
+    }
+
+    fn heuristic(&self, from: &(usize, usize), goal: &(usize, usize)) -> u32 {
+        self.rules.heuristic(from, goal)
+    }
+
+    fn successors(&self, node: &(usize, usize)) -> Vec<((usize, usize), u32)> {
+        self.map.moves(&self.rules, node)
+    }
+}
+
+#[cfg(test)]
+mod rules_12_tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzle_rules() {
+        let rules = ClimbingRules::default();
+        assert!(rules.allows(b'a', b'b'));
+        assert!(rules.allows(b'c', b'c'));
+        assert!(!rules.allows(b'a', b'c'));
+        assert!(rules.allows(b'z', b'a'));
+        assert_eq!(rules.cost(b'a', b'b'), 1);
+        assert_eq!(rules.cost(b'z', b'a'), 1);
+
+        let reversed = rules.reversed();
+        for from in b'a'..=b'z' {
+            for to in b'a'..=b'z' {
+                assert_eq!(reversed.allows(from, to), rules.allows(to, from));
+            }
+        }
+    }
+
+    #[test]
+    fn test_costs() {
+        let rules = ClimbingRules { max_ascent: None, max_descent: Some(2), ascent_cost: 10, descent_cost: 1, ..Default::default() };
+        assert!(rules.allows(b'a', b'z'));
+        assert!(rules.allows(b'e', b'c'));
+        assert!(!rules.allows(b'e', b'b'));
+        assert_eq!(rules.cost(b'a', b'c'), 21);
+        assert_eq!(rules.cost(b'c', b'a'), 3);
+        assert_eq!(rules.cost(b'c', b'c'), 1);
+        assert_eq!(rules.reversed().cost(b'a', b'c'), rules.cost(b'c', b'a'));
+    }
+
+    #[test]
+    fn test_weighted_route() {
+        // Straight over the 'm' is 2 moves, around the bottom is 6.
+        let map: ElevationMap = "Smaa\namaE\naaaa".parse().unwrap();
+        let steep = ClimbingRules { max_ascent: None, ascent_cost: 10, descent_cost: 1, ..Default::default() };
+
+        assert_eq!(map.with_rules(steep).bfs(map.start, (0, 2)).unwrap().cost, 2);
+        let cheapest = map.with_rules(steep).dijkstra(map.start, (0, 2)).unwrap();
+        assert_eq!(cheapest.cost, 6);
+        assert_eq!(cheapest.nodes, vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2)]);
+        assert_eq!(map.with_rules(steep).astar(map.start, (0, 2)).unwrap().cost, 6);
+
+        let flat = ClimbingRules { max_ascent: None, ..Default::default() };
+        assert_eq!(map.with_rules(flat).dijkstra(map.start, (0, 2)).unwrap().cost, 2);
+    }
+
+    #[test]
+    fn test_diagonal() {
+        let map: ElevationMap = "Sab\nabc\nbcE".parse().unwrap();
+        assert_eq!(map.adjacent(Movement::FourWay, &(0, 0)), vec![(1, 0), (0, 1)]);
+        assert_eq!(map.adjacent(Movement::EightWay, &(1, 1)).len(), 8);
+
+        let diagonal = ClimbingRules { max_ascent: None, movement: Movement::EightWay, ..Default::default() };
+        let path = map.with_rules(diagonal).astar(map.start, map.end).unwrap();
+        assert_eq!(path.nodes, vec![(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(map.with_rules(diagonal).bfs(map.start, map.end).unwrap().cost, 2);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        let map = ElevationMap::new("inputs/12.inputs.txt");
+        let puzzle = map.with_rules(ClimbingRules::PUZZLE);
+        assert_eq!(puzzle.bfs(map.start, map.end).unwrap().cost, 437);
+        assert_eq!(puzzle.astar(map.start, map.end).unwrap().cost, 437);
+        for row in 0..map.rows() {
+            for column in 0..map.columns() {
+                let rev = map.rev_moves(&ClimbingRules::PUZZLE, &(row, column));
+                for (next, _) in rev {
+                    assert!(puzzle.neighbors(&next).contains(&(row, column)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+/// Key concepts:
+/// * Seeded random number generators (rand::rngs::StdRng): the same seed always builds the same map,
+///   so a failing property test can be replayed.
+/// * Property testing by hand: generate lots of maps, check that every search agrees on each one.
+use super::ElevationMap;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Terrain {
+    /// Rolling hills from a 2D random walk, tilted upwards from S towards E.
+    /// Usually, but not always, climbable.
+    RandomWalk,
+    /// A slope rising from 'a' (left edge, where S is) to 'z' (right edge, where E is),
+    /// cut by steep north-south ridges that can only be crossed through random gaps.
+    /// Reachable whenever there are at least `MIN_ROUTE` columns.
+    Ridges,
+    /// A single winding corridor that spirals from S in the top-left corner to E in the middle,
+    /// walled off by 'z' cliffs. Reachable whenever the corridor has at least `MIN_ROUTE` cells,
+    /// and then the shortest route walks the whole corridor.
+    Spiral,
+    /// A `RandomWalk` map with a pit dug around E, so E can never be reached under the puzzle's rule.
+    Unreachable,
+}
+
+const LOW: u8 = b'a';
+const HIGH: u8 = b'z';
+
+/// Climbing one level per step, a route from 'a' at S to 'z' at E needs at least this many cells.
+/// Smaller `Ridges` and `Spiral` maps still climb one level per step, but run out of room before E.
+pub const MIN_ROUTE: usize = (HIGH - LOW) as usize + 1;
+
+/// Builds a `rows` × `columns` map. `Spiral` maps need at least 3 × 3 cells.
+pub fn generate(terrain: Terrain, rows: usize, columns: usize, seed: u64) -> ElevationMap {
+    assert!(rows > 0 && columns > 1, "terrain needs room for both S and E");
+    let mut rng = StdRng::seed_from_u64(seed);
+    match terrain {
+        Terrain::RandomWalk => random_walk(&mut rng, rows, columns),
+        Terrain::Ridges => ridges(&mut rng, rows, columns),
+        Terrain::Spiral => spiral(rows, columns),
+        Terrain::Unreachable => unreachable(&mut rng, rows, columns),
+    }
+}
+
+fn random_walk(rng: &mut StdRng, rows: usize, columns: usize) -> ElevationMap {
+    let start = (rng.gen_range(0..rows), rng.gen_range(0..columns / 2));
+    let end = loop {
+        let end = (rng.gen_range(0..rows), rng.gen_range(columns / 2..columns));
+        if end != start {
+            break end;
+        }
+    };
+
+    // Each cell wanders at most one step away from the average of the cells above and to its left.
+    let mut noise: Vec<Vec<i32>> = vec![vec![0; columns]; rows];
+    for row in 0..rows {
+        for column in 0..columns {
+            let base = match (row, column) {
+                (0, 0) => 0,
+                (0, c) => noise[0][c - 1],
+                (r, 0) => noise[r - 1][0],
+                (r, c) => (noise[r - 1][c] + noise[r][c - 1]) / 2,
+            };
+            noise[row][column] = (base + rng.gen_range(-1..=1)).clamp(-3, 3);
+        }
+    }
+
+    let total = distance(start, end) as i32;
+    let heights = (0..rows).map(|row| {
+        (0..columns).map(|column| {
+            let progress = 25 * distance(start, (row, column)) as i32 / total;
+            (LOW as i32 + progress + noise[row][column]).clamp(LOW as i32, HIGH as i32) as u8
+        }).collect()
+    }).collect();
+
+    with_endpoints(heights, start, end)
+}
+
+fn ridges(rng: &mut StdRng, rows: usize, columns: usize) -> ElevationMap {
+    // Spread the climb over the whole width, but never more than one level per column.
+    let climb = (HIGH - LOW) as usize;
+    let mut heights: Vec<Vec<u8>> = (0..rows).map(|_| {
+        (0..columns).map(|column| LOW + (column * climb / (columns - 1).max(climb)) as u8).collect()
+    }).collect();
+
+    // Ridges never touch, so there is always a column to walk along between two gaps.
+    // They go strictly between the first and last column, so narrower maps get none.
+    let mut ridge_columns: Vec<usize> = Vec::new();
+    let ridges = if columns < 3 { 0 } else { rng.gen_range(1..=(columns / 4).max(1)) };
+    for _ in 0..ridges {
+        let column = rng.gen_range(1..columns - 1);
+        if ridge_columns.iter().any(|ridge| ridge.abs_diff(column) < 2) {
+            continue;
+        }
+        ridge_columns.push(column);
+        let gaps: Vec<usize> = (0..rng.gen_range(1..=3)).map(|_| rng.gen_range(0..rows)).collect();
+        for (row, heights) in heights.iter_mut().enumerate() {
+            if !gaps.contains(&row) {
+                heights[column] = (heights[column] + 3).min(HIGH);
+            }
+        }
+    }
+
+    let start = (rng.gen_range(0..rows), 0);
+    let end = (rng.gen_range(0..rows), columns - 1);
+    with_endpoints(heights, start, end)
+}
+
+/// Concentric square rings, alternating corridor and wall. Every corridor ring has a wall cell
+/// right after its entrance, so it has to be walked all the way round, and a gap at the end of
+/// the lap leads into the next ring in. The corridor climbs evenly from 'a' at S to 'y' next to E,
+/// one level per cell at most, so corridors shorter than `MIN_ROUTE` never get high enough.
+fn spiral(rows: usize, columns: usize) -> ElevationMap {
+    assert!(rows >= 3 && columns >= 3, "spirals need at least 3 × 3 cells");
+    let ring = |(row, column): (usize, usize)| row.min(column).min(rows - 1 - row).min(columns - 1 - column);
+    let is_corridor = |(row, column): (usize, usize)| {
+        let depth = ring((row, column));
+        if depth % 2 == 0 {
+            (row, column) != (depth + 1, depth)       // blocker after the entrance
+        } else {
+            (row, column) == (depth + 1, depth)       // gap into the next ring
+        }
+    };
+
+    // Walk the corridor from S. Any corridor cell the walk doesn't reach becomes wall.
+    let mut corridor: Vec<(usize, usize)> = vec![(0, 0)];
+    let mut visited = vec![vec![false; columns]; rows];
+    visited[0][0] = true;
+    loop {
+        let (row, column) = *corridor.last().unwrap();
+        let next = [(-1, 0), (0, 1), (1, 0), (0, -1)].iter()
+            .filter_map(|&(dr, dc)| Some((row.checked_add_signed(dr)?, column.checked_add_signed(dc)?)))
+            .find(|&(r, c)| r < rows && c < columns && !visited[r][c] && is_corridor((r, c)));
+        match next {
+            Some((r, c)) => {
+                visited[r][c] = true;
+                corridor.push((r, c));
+            }
+            None => break,
+        }
+    }
+
+    let mut heights = vec![vec![HIGH; columns]; rows];
+    let last = corridor.len() - 1;
+    let climb = (HIGH - LOW - 1) as usize;
+    for (i, &(row, column)) in corridor.iter().enumerate() {
+        heights[row][column] = LOW + (i * climb / last.saturating_sub(1).max(climb)).min(climb) as u8;
+    }
+
+    with_endpoints(heights, corridor[0], corridor[last])
+}
+
+fn unreachable(rng: &mut StdRng, rows: usize, columns: usize) -> ElevationMap {
+    let mut map = random_walk(rng, rows, columns);
+    let (end_row, end_column) = map.end;
+    for row in end_row.saturating_sub(1)..=(end_row + 1).min(rows - 1) {
+        for column in end_column.saturating_sub(1)..=(end_column + 1).min(columns - 1) {
+            if (row, column) != map.end && (row, column) != map.start {
+                map.heights[row][column] = LOW;
+            }
+        }
+    }
+    map
+}
+
+fn with_endpoints(mut heights: Vec<Vec<u8>>, start: (usize, usize), end: (usize, usize)) -> ElevationMap {
+    heights[start.0][start.1] = LOW;
+    heights[end.0][end.1] = HIGH;
+    ElevationMap { heights, start, end }
+}
+
+fn distance(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+#[cfg(test)]
+mod terrain_12_tests {
+    use super::*;
+    use crate::day_12::distance_field;
+    use crate::day_12::rules::ClimbingRules;
+    use crate::pathfinding::Graph;
+
+    const KINDS: [Terrain; 4] = [Terrain::RandomWalk, Terrain::Ridges, Terrain::Spiral, Terrain::Unreachable];
+
+    /// Every consecutive pair of cells on a path must be a legal puzzle move.
+    fn assert_valid_path(map: &ElevationMap, nodes: &[(usize, usize)]) {
+        assert_eq!(nodes.first(), Some(&map.start));
+        assert_eq!(nodes.last(), Some(&map.end));
+        for pair in nodes.windows(2) {
+            assert!(map.neighbors(&pair[0]).contains(&pair[1]), "{:?} -> {:?}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_seeded() {
+        for kind in KINDS {
+            assert_eq!(generate(kind, 20, 30, 7), generate(kind, 20, 30, 7));
+        }
+        assert_ne!(generate(Terrain::RandomWalk, 20, 30, 7), generate(Terrain::RandomWalk, 20, 30, 8));
+    }
+
+    #[test]
+    fn test_well_formed() {
+        for kind in KINDS {
+            for seed in 0..20 {
+                let map = generate(kind, 15, 25, seed);
+                assert_eq!((map.rows(), map.columns()), (15, 25));
+                assert_ne!(map.start, map.end);
+                assert_eq!(map.height(&map.start), b'a');
+                assert_eq!(map.height(&map.end), b'z');
+                assert!(map.heights.iter().flatten().all(|h| h.is_ascii_lowercase()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_searches_agree() {
+        for kind in KINDS {
+            for seed in 0..25 {
+                let map = generate(kind, 24, 32, seed);
+                let bfs = map.bfs(map.start, map.end);
+                let expected = bfs.as_ref().map(|path| path.cost);
+
+                assert_eq!(map.dijkstra(map.start, map.end).map(|path| path.cost), expected, "{kind:?} {seed}");
+                assert_eq!(map.astar(map.start, map.end).map(|path| path.cost), expected, "{kind:?} {seed}");
+                assert_eq!(map.bidirectional_bfs(map.start, map.end).map(|path| path.cost), expected, "{kind:?} {seed}");
+                assert_eq!(map.with_rules(ClimbingRules::PUZZLE).bfs(map.start, map.end).map(|path| path.cost), expected);
+                assert_eq!(distance_field(&map, map.end).get(&map.start), expected, "{kind:?} {seed}");
+
+                if let Some(path) = bfs {
+                    assert_valid_path(&map, &path.nodes);
+                    assert_valid_path(&map, &map.bidirectional_bfs(map.start, map.end).unwrap().nodes);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unreachable() {
+        for seed in 0..50 {
+            let map = generate(Terrain::Unreachable, 20, 20, seed);
+            assert_eq!(map.bfs(map.start, map.end), None, "seed {seed}");
+            let error = map.route().unwrap_err();
+            assert!(error.reachable.contains(&map.start));
+            assert!(!error.reachable.contains(&map.end));
+        }
+    }
+
+    #[test]
+    fn test_ridges_reachable() {
+        for seed in 0..50 {
+            let map = generate(Terrain::Ridges, 20, 40, seed);
+            assert!(map.bfs(map.start, map.end).is_some(), "seed {seed}");
+        }
+    }
+
+    /// Tiny maps can't climb all the way to 'z', but everything short of E must still be
+    /// walkable, and E reachable exactly when the route is long enough.
+    #[test]
+    fn test_small_sizes() {
+        for (rows, columns) in [(1, 2), (2, 2), (2, 3), (2, 13), (2, 25), (2, 26), (2, 40), (1, 30), (3, 3), (5, 5)] {
+            for seed in 0..10 {
+                let map = generate(Terrain::Ridges, rows, columns, seed);
+                // Every column has a gap, so its lowest cell is the slope; it rises one level at most.
+                let slope: Vec<u8> = (0..columns - 1).map(|c| (0..rows).map(|r| map.heights[r][c]).min().unwrap()).collect();
+                assert!(slope.windows(2).all(|pair| pair[1] <= pair[0] + 1), "{rows}x{columns} seed {seed}: {slope:?}");
+                match map.route() {
+                    // One column short, the last column can hold a 'y' next to E, depending on the gaps.
+                    Ok(path) => {
+                        assert!(columns + 1 >= MIN_ROUTE, "{rows}x{columns} seed {seed}");
+                        assert_valid_path(&map, &path.nodes);
+                    }
+                    Err(error) => {
+                        assert!(columns < MIN_ROUTE, "{rows}x{columns} seed {seed}");
+                        // The climb still gets right up to E's column.
+                        assert!((0..rows).any(|r| error.reachable.contains(&(r, columns - 2))), "{rows}x{columns} seed {seed}");
+                    }
+                }
+            }
+        }
+
+        for (rows, columns) in [(3, 3), (3, 4), (4, 4), (5, 5), (6, 6), (7, 7), (3, 30), (9, 9)] {
+            let map = generate(Terrain::Spiral, rows, columns, 0);
+            let cells = || (0..rows).flat_map(|r| (0..columns).map(move |c| (r, c)));
+            let corridor: Vec<(usize, usize)> = cells().filter(|cell| map.height(cell) < HIGH).collect();
+            match map.route() {
+                Ok(path) => {
+                    assert!(corridor.len() + 1 >= MIN_ROUTE, "{rows}x{columns}");
+                    assert_valid_path(&map, &path.nodes);
+                    assert_eq!(path.cost as usize, corridor.len());
+                }
+                Err(error) => {
+                    assert!(corridor.len() + 1 < MIN_ROUTE, "{rows}x{columns}");
+                    assert!(corridor.iter().all(|cell| error.reachable.contains(cell)), "{rows}x{columns}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_spiral_is_long() {
+        let map = generate(Terrain::Spiral, 30, 30, 0);
+        let path = map.bfs(map.start, map.end).unwrap();
+        assert_valid_path(&map, &path.nodes);
+        // Every other ring is corridor, so about half the map is on the route.
+        assert!(path.cost > 30 * 30 / 2 - 30, "{}", path.cost);
+        assert_eq!(path.cost, map.astar(map.start, map.end).unwrap().cost);
+    }
+}
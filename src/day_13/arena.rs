@@ -0,0 +1,221 @@
+/// Key concepts:
+/// * Flat encoding: a packet is a run of tokens in pre-order, and a list token stores how many
+///   tokens its body spans, so a whole sub-list can be skipped in O(1).
+/// * Arena: every packet lives in one shared token buffer and is referred to by a PacketId,
+///   so a million packets cost two allocations instead of one Vec per nesting level.
+/// * Promotion for free: the body of `[n]` is the single token `n`, so an integer is
+///   promoted to a list by comparing its one-token slice as a list body.
+use std::cmp::Ordering;
+use std::fmt::Display;
+use super::{Item, List};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Num(u32),
+    /// A list whose body is the next `n` tokens.
+    List(u32),
+}
+
+impl Token {
+    /// Number of tokens taken by the item starting with this token.
+    fn span(self) -> usize {
+        match self {
+            Token::Num(_) => 1,
+            Token::List(n) => 1 + n as usize,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PacketId(u32);
+
+/// A packet borrowed from a PacketArena: the body of its outermost list.
+#[derive(Debug, Clone, Copy)]
+pub struct Packet<'a>(&'a [Token]);
+
+#[derive(Debug, Default)]
+pub struct PacketArena {
+    tokens: Vec<Token>,
+    /// Start and length of each packet's body in `tokens`.
+    spans: Vec<(u32, u32)>,
+}
+
+impl PacketArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, list: &List) -> PacketId {
+        let start = self.tokens.len();
+        self.flatten(&list.0);
+        let id = PacketId(self.spans.len() as u32);
+        self.spans.push((start as u32, (self.tokens.len() - start) as u32));
+        id
+    }
+
+    fn flatten(&mut self, items: &[Item]) {
+        for item in items {
+            match item {
+                Item::Num(n) => self.tokens.push(Token::Num(*n)),
+                Item::List(l) => {
+                    let at = self.tokens.len();
+                    self.tokens.push(Token::List(0));
+                    self.flatten(&l.0);
+                    self.tokens[at] = Token::List((self.tokens.len() - at - 1) as u32);
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, id: PacketId) -> Packet<'_> {
+        let (start, len) = self.spans[id.0 as usize];
+        Packet(&self.tokens[start as usize..(start + len) as usize])
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Every packet, in insertion order.
+    pub fn packets(&self) -> impl Iterator<Item = Packet<'_>> {
+        (0..self.spans.len() as u32).map(|i| self.get(PacketId(i)))
+    }
+
+    /// Ids of every packet, sorted by packet order. Ties keep insertion order.
+    pub fn sorted(&self) -> Vec<PacketId> {
+        let mut ids: Vec<PacketId> = (0..self.spans.len() as u32).map(PacketId).collect();
+        ids.sort_by(|a, b| self.get(*a).cmp(&self.get(*b)));
+        ids
+    }
+}
+
+impl<'a> FromIterator<&'a List> for PacketArena {
+    fn from_iter<T: IntoIterator<Item = &'a List>>(iter: T) -> Self {
+        let mut arena = PacketArena::new();
+        for list in iter {
+            arena.push(list);
+        }
+        arena
+    }
+}
+
+/// Compares two list bodies item by item, then by length.
+fn cmp_bodies(left: &[Token], right: &[Token]) -> Ordering {
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        let (l, r) = (left[i].span(), right[j].span());
+        let o = cmp_items(&left[i..i + l], &right[j..j + r]);
+        if o != Ordering::Equal {
+            return o;
+        }
+        i += l;
+        j += r;
+    }
+    (left.len() - i).cmp(&(right.len() - j))
+}
+
+/// Compares two items, each given as the exact slice of tokens it spans.
+fn cmp_items(left: &[Token], right: &[Token]) -> Ordering {
+    match (left[0], right[0]) {
+        (Token::Num(l), Token::Num(r)) => l.cmp(&r),
+        (Token::List(_), Token::List(_)) => cmp_bodies(&left[1..], &right[1..]),
+        (Token::Num(_), Token::List(_)) => cmp_bodies(left, &right[1..]),
+        (Token::List(_), Token::Num(_)) => cmp_bodies(&left[1..], right),
+    }
+}
+
+impl Ord for Packet<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_bodies(self.0, other.0)
+    }
+}
+
+impl PartialOrd for Packet<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Same equality as List: `[1] == [[1]]`.
+impl PartialEq for Packet<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Packet<'_> {}
+
+/// Prints the same compact form as List.
+impl Display for Packet<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        let mut i = 0;
+        while i < self.0.len() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            let token = self.0[i];
+            match token {
+                Token::Num(n) => write!(f, "{n}")?,
+                Token::List(n) => write!(f, "{}", Packet(&self.0[i + 1..i + 1 + n as usize]))?,
+            }
+            i += token.span();
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod arena_13_tests {
+    use super::*;
+    use super::super::{ordered_pair_sum, random_packet, read_packets};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_flatten() {
+        let mut arena = PacketArena::new();
+        let id = arena.push(&List::new("[1,[2,[]],3]".to_string()));
+        assert_eq!(arena.get(id).0, [Token::Num(1), Token::List(2), Token::Num(2), Token::List(0), Token::Num(3)]);
+        assert_eq!(arena.get(id).to_string(), "[1,[2,[]],3]");
+
+        let empty = arena.push(&List::new("[]".to_string()));
+        assert_eq!(arena.get(empty).to_string(), "[]");
+        assert_eq!(arena.len(), 2);
+    }
+
+    #[test]
+    fn test_solver() {
+        let lists = read_packets("inputs/13.inputs.txt");
+        let arena: PacketArena = lists.iter().collect();
+        let packets: Vec<Packet> = arena.packets().collect();
+        assert_eq!(ordered_pair_sum(&packets), 13);
+
+        let one = List::new("[1]".to_string());
+        let nested = List::new("[[[1]]]".to_string());
+        let arena: PacketArena = [&one, &nested].into_iter().collect();
+        assert_eq!(arena.get(PacketId(0)), arena.get(PacketId(1)));
+    }
+
+    #[test]
+    fn test_agrees_with_list() {
+        let mut rng = StdRng::seed_from_u64(40);
+        let lists: Vec<List> = (0..2_000).map(|_| random_packet(&mut rng, 4)).collect();
+        let arena: PacketArena = lists.iter().collect();
+
+        for (pair, packets) in lists.chunks(2).zip(arena.packets().collect::<Vec<_>>().chunks(2)) {
+            assert_eq!(pair[0].cmp(&pair[1]), packets[0].cmp(&packets[1]), "{} vs {}", pair[0], pair[1]);
+            assert_eq!(pair[0].to_string(), packets[0].to_string());
+        }
+
+        let mut sorted: Vec<&List> = lists.iter().collect();
+        sorted.sort();
+        let ids = arena.sorted();
+        for (list, id) in sorted.iter().zip(ids) {
+            assert_eq!(list.to_string(), arena.get(id).to_string());
+        }
+    }
+}
@@ -0,0 +1,291 @@
+/// Key concepts:
+/// * Same comparison as `Ord for Item`, but recording every step on the way to the decision,
+///   so a surprising result can be explained in the words of the puzzle statement.
+/// * Keeping the record as data (Step, Decision) and rendering it separately with Display.
+use std::cmp::Ordering;
+use std::fmt::Display;
+use super::{write_compact, Item, List};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Side::Left => write!(f, "left"),
+            Side::Right => write!(f, "right"),
+        }
+    }
+}
+
+/// Why the comparison stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// Two integers differed; the side holds the smaller one.
+    Smaller(Side),
+    /// One list ran out of items first.
+    RanOut(Side),
+}
+
+impl Reason {
+    /// Whether the packets are in the right order.
+    pub fn ordered(&self) -> bool {
+        matches!(self, Reason::Smaller(Side::Left) | Reason::RanOut(Side::Left))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepKind {
+    /// `Compare left vs right`, both in compact form.
+    Compare { left: String, right: String },
+    /// An integer on `side` was wrapped in a list, shown as `to`.
+    Promote { side: Side, to: String },
+    Decide(Reason),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Indentation level in the narrative. The top-level compare is 0.
+    pub depth: usize,
+    pub kind: StepKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision {
+    /// Index taken at each level of nesting to reach the deciding items. For `RanOut`
+    /// the last index is the first one missing on the shorter side.
+    pub path: Vec<usize>,
+    /// Whether an integer was promoted to a list anywhere along `path`.
+    pub promoted: bool,
+    pub reason: Reason,
+}
+
+impl Decision {
+    /// How many lists deep the decision was made; 1 means directly in the outer packets.
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+    /// None if the packets compare equal.
+    pub decision: Option<Decision>,
+}
+
+impl Trace {
+    /// Agrees with `left.cmp(right)`.
+    pub fn ordering(&self) -> Ordering {
+        match &self.decision {
+            None => Ordering::Equal,
+            Some(d) if d.reason.ordered() => Ordering::Less,
+            Some(_) => Ordering::Greater,
+        }
+    }
+}
+
+/// Renders the trace as the indented list used in the puzzle statement.
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            write!(f, "{:indent$}- ", "", indent = 2 * step.depth)?;
+            match &step.kind {
+                StepKind::Compare { left, right } => writeln!(f, "Compare {left} vs {right}")?,
+                StepKind::Promote { side, to } => writeln!(f, "Mixed types; convert {side} to {to} and retry comparison")?,
+                StepKind::Decide(reason) => {
+                    match reason {
+                        Reason::Smaller(Side::Left) => write!(f, "Left side is smaller")?,
+                        Reason::Smaller(Side::Right) => write!(f, "Right side is smaller")?,
+                        Reason::RanOut(Side::Left) => write!(f, "Left side ran out of items")?,
+                        Reason::RanOut(Side::Right) => write!(f, "Right side ran out of items")?,
+                    }
+                    let not = if reason.ordered() { "" } else { "not " };
+                    writeln!(f, ", so inputs are {not}in the right order")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Tracer {
+    steps: Vec<Step>,
+    path: Vec<usize>,
+    /// Promotions on the current path that haven't been compared to completion yet.
+    promotions: usize,
+    decision: Option<Decision>,
+}
+
+fn compact(items: &[Item]) -> String {
+    let mut s = String::new();
+    write_compact(items, &mut s).unwrap();
+    s
+}
+
+fn item_string(item: &Item) -> String {
+    match item {
+        Item::Num(n) => n.to_string(),
+        Item::List(l) => compact(&l.0),
+    }
+}
+
+impl Tracer {
+    fn push(&mut self, depth: usize, kind: StepKind) {
+        self.steps.push(Step { depth, kind });
+    }
+
+    fn decide(&mut self, depth: usize, reason: Reason) {
+        self.push(depth, StepKind::Decide(reason));
+        self.decision = Some(Decision { path: self.path.clone(), promoted: self.promotions > 0, reason });
+    }
+
+    fn lists(&mut self, left: &[Item], right: &[Item], depth: usize) -> Ordering {
+        self.push(depth, StepKind::Compare { left: compact(left), right: compact(right) });
+        for (i, (l, r)) in left.iter().zip(right).enumerate() {
+            self.path.push(i);
+            let o = self.items(l, r, depth + 1);
+            if o != Ordering::Equal {
+                return o;
+            }
+            self.path.pop();
+        }
+
+        let o = left.len().cmp(&right.len());
+        if o != Ordering::Equal {
+            self.path.push(left.len().min(right.len()));
+            let side = if o == Ordering::Less { Side::Left } else { Side::Right };
+            self.decide(depth + 1, Reason::RanOut(side));
+        }
+        o
+    }
+
+    fn items(&mut self, left: &Item, right: &Item, depth: usize) -> Ordering {
+        match (left, right) {
+            (Item::Num(l), Item::Num(r)) => {
+                self.push(depth, StepKind::Compare { left: l.to_string(), right: r.to_string() });
+                let o = l.cmp(r);
+                if o != Ordering::Equal {
+                    let side = if o == Ordering::Less { Side::Left } else { Side::Right };
+                    self.decide(depth + 1, Reason::Smaller(side));
+                }
+                o
+            }
+            (Item::List(l), Item::List(r)) => self.lists(&l.0, &r.0, depth),
+            (Item::Num(_), Item::List(r)) => {
+                self.promoted(Side::Left, std::slice::from_ref(left), &r.0, depth)
+            }
+            (Item::List(l), Item::Num(_)) => {
+                self.promoted(Side::Right, &l.0, std::slice::from_ref(right), depth)
+            }
+        }
+    }
+
+    /// Compares after wrapping the integer on `side`, which is already a one-item slice.
+    fn promoted(&mut self, side: Side, left: &[Item], right: &[Item], depth: usize) -> Ordering {
+        let (l, r) = match side {
+            Side::Left => (item_string(&left[0]), compact(right)),
+            Side::Right => (compact(left), item_string(&right[0])),
+        };
+        self.push(depth, StepKind::Compare { left: l, right: r });
+        let to = compact(if side == Side::Left { left } else { right });
+        self.push(depth + 1, StepKind::Promote { side, to });
+
+        self.promotions += 1;
+        let o = self.lists(left, right, depth + 1);
+        if o == Ordering::Equal {
+            self.promotions -= 1;
+        }
+        o
+    }
+}
+
+/// Compares two packets like `left.cmp(right)`, recording why they compare that way.
+pub fn explain(left: &List, right: &List) -> Trace {
+    let mut tracer = Tracer { steps: Vec::new(), path: Vec::new(), promotions: 0, decision: None };
+    tracer.lists(&left.0, &right.0, 0);
+    Trace { steps: tracer.steps, decision: tracer.decision }
+}
+
+#[cfg(test)]
+mod explain_13_tests {
+    use super::*;
+    use super::super::random_packet;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn trace(left: &str, right: &str) -> Trace {
+        explain(&List::new(left.to_string()), &List::new(right.to_string()))
+    }
+
+    #[test]
+    fn test_narrative() {
+        assert_eq!(trace("[1,1,3,1,1]", "[1,1,5,1,1]").to_string(), "\
+- Compare [1,1,3,1,1] vs [1,1,5,1,1]
+  - Compare 1 vs 1
+  - Compare 1 vs 1
+  - Compare 3 vs 5
+    - Left side is smaller, so inputs are in the right order
+");
+        assert_eq!(trace("[[1],[2,3,4]]", "[[1],4]").to_string(), "\
+- Compare [[1],[2,3,4]] vs [[1],4]
+  - Compare [1] vs [1]
+    - Compare 1 vs 1
+  - Compare [2,3,4] vs 4
+    - Mixed types; convert right to [4] and retry comparison
+    - Compare [2,3,4] vs [4]
+      - Compare 2 vs 4
+        - Left side is smaller, so inputs are in the right order
+");
+        assert_eq!(trace("[9]", "[[8,7,6]]").to_string(), "\
+- Compare [9] vs [[8,7,6]]
+  - Compare 9 vs [8,7,6]
+    - Mixed types; convert left to [9] and retry comparison
+    - Compare [9] vs [8,7,6]
+      - Compare 9 vs 8
+        - Right side is smaller, so inputs are not in the right order
+");
+        assert_eq!(trace("[7,7,7,7]", "[7,7,7]").to_string(), "\
+- Compare [7,7,7,7] vs [7,7,7]
+  - Compare 7 vs 7
+  - Compare 7 vs 7
+  - Compare 7 vs 7
+  - Right side ran out of items, so inputs are not in the right order
+");
+        assert_eq!(trace("[]", "[3]").to_string(), "\
+- Compare [] vs [3]
+  - Left side ran out of items, so inputs are in the right order
+");
+    }
+
+    #[test]
+    fn test_decision() {
+        let t = trace("[[1],[2,3,4]]", "[[1],4]");
+        let d = t.decision.unwrap();
+        assert_eq!((d.path, d.promoted, d.reason), (vec![1, 0], true, Reason::Smaller(Side::Left)));
+
+        // The promotion at index 0 compared equal, so it isn't on the deciding path.
+        let d = trace("[1,[2]]", "[[1],[3]]").decision.unwrap();
+        assert_eq!((d.depth(), d.promoted), (2, false));
+
+        let d = trace("[[[]]]", "[[]]").decision.unwrap();
+        assert_eq!((d.path, d.promoted, d.reason), (vec![0, 0], false, Reason::RanOut(Side::Right)));
+
+        let t = trace("[1,[2]]", "[[1],2]");
+        assert_eq!(t.decision, None);
+        assert_eq!(t.ordering(), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_agrees_with_ord() {
+        let mut rng = StdRng::seed_from_u64(39);
+        for _ in 0..5_000 {
+            let left = random_packet(&mut rng, 3);
+            let right = random_packet(&mut rng, 3);
+            let t = explain(&left, &right);
+            assert_eq!(t.ordering(), left.cmp(&right), "{left} vs {right}\n{t}");
+        }
+    }
+}
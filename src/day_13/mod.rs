@@ -1,8 +1,25 @@
 /// Key concepts:
 /// * Idiomatic deserialization: TryFrom ??
-/// * Production-ready deserialization: SERDE!!!
+/// * Production-ready deserialization: SERDE!!! (see serde.rs)
+/// * Hand-written recursive descent parser with positioned errors (see parser.rs)
+/// * Flat, arena-allocated packets for large inputs (see arena.rs)
+/// * Explaining a decision, not just making it: the comparison trace in explain.rs
+/// * Display with an alternate `{:#}` form via Formatter::alternate()
+/// * Implementing Ord by hand. Vec<T>'s own Ord is already the puzzle's list rule
+///   (item by item, then the shorter list first), so List can derive it once Item is Ord.
 /// 
 
+mod arena;
+mod explain;
+mod parser;
+mod serde;
+
+pub use arena::{Packet, PacketArena, PacketId};
+pub use explain::{explain, Decision, Reason, Side, Step, StepKind, Trace};
+pub use parser::{ParseError, ParseErrorKind};
+
+use rand::Rng;
+use std::cmp::Ordering;
 use std::fmt::Display;
 
 #[derive(Debug)]
@@ -11,120 +28,141 @@ enum Item {
     List(List),
 }
 
-pub fn packets_are_ordered(left: &List, right: &List) -> Option<bool> {
-    let len = left.0.len().min(right.0.len());
-    for i in 0..len {
-        match (&left.0[i], &right.0[i]) {
-            (Item::Num(l), Item::Num(r)) => if l == r {continue} else {return Some(l < r)},
-            (Item::List(l), Item::List(r)) => {
-                let o = packets_are_ordered(&l, &r);
-                if o.is_none() {continue} else {return o}
-            },
-            (Item::Num(l), Item::List(r)) => {
-                let o = packets_are_ordered(&List(vec![Item::Num(*l)]), &r);
-                if o.is_none() {continue} else {return o}
-            },
-            (Item::List(l), Item::Num(r)) => {
-                let o = packets_are_ordered( &l, &List(vec![Item::Num(*r)]));
-                if o.is_none() {continue} else {return o}
-            },
+/// Integers compare as numbers, lists compare as lists, and an integer compared with a list
+/// is treated as a one-item list. Equality follows the same rule, so `1 == [1] == [[1]]`.
+impl Ord for Item {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Item::Num(l), Item::Num(r)) => l.cmp(r),
+            (Item::List(l), Item::List(r)) => l.cmp(r),
+            (Item::Num(_), Item::List(r)) => std::slice::from_ref(self).cmp(&r.0[..]),
+            (Item::List(l), Item::Num(_)) => l.0[..].cmp(std::slice::from_ref(other)),
         }
     }
-    if left.0.len() == right.0.len() {None} else {Some(left.0.len() < right.0.len())}
 }
 
-#[derive(Debug)]
+impl PartialOrd for Item {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Item {}
+
+/// Some(true) if the packets are in the right order, None if neither comes first.
+pub fn packets_are_ordered(left: &List, right: &List) -> Option<bool> {
+    match left.cmp(right) {
+        Ordering::Less => Some(true),
+        Ordering::Equal => None,
+        Ordering::Greater => Some(false),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct List(Vec<Item>);
 impl List {
+    /// Parses a packet, panicking with the error position if it is malformed. See `List::parse`.
     pub fn new(s: String) -> Self {
-        Self::new_list(&s[..]).0
-    }
-    
-    fn new_list(s: &str) -> (Self, &str) {
-        let mut v: Vec<Item> = Vec::new();
-
-        let mut num: Option<u32> = None;
-        let mut next_token = Token::OpenBracket;    //placeholder
-        let mut s = s;
-
-        loop {
-            let result = Token::next_token(s);
-            if result.is_err() {
-                break;
-            } else {
-                (num, next_token, s) = result.unwrap();
-            }
-            if let Some(n) = num {
-                v.push(Item::Num(n));
-            }
-            match next_token {
-                Token::CloseBracket => break,
-                Token::Comma => continue,
-                Token::OpenBracket => {
-                    let result = Self::new_list(s);
-                    v.push(Item::List(result.0));
-                    s = result.1;
-                },
-            }
-        }
-
-        (List(v), s)
+        Self::parse(&s).unwrap_or_else(|e| panic!("invalid packet {s:?}: {e}"))
     }
 }
 
+/// `{}` prints the canonical compact form, e.g. `[1,[2,3],[]]`.
+/// `{:#}` pretty-prints one item per line, indenting nested lists by two spaces.
+/// Both forms parse back to the same packet with `List::new`.
 impl Display for List {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        s.push('[');
-        for item in &self.0 {
+        if f.alternate() {
+            self.fmt_pretty(f, 0)
+        } else {
+            self.fmt_compact(f)
+        }
+    }
+}
+
+impl List {
+    fn fmt_compact(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_compact(&self.0, f)
+    }
+
+    fn fmt_pretty(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "[]");
+        }
+        writeln!(f, "[")?;
+        for (i, item) in self.0.iter().enumerate() {
+            write!(f, "{:indent$}", "", indent = 2 * (depth + 1))?;
             match item {
-                Item::Num(n) => {
-                    s.push_str(format!("{n}, ").as_str());
-                }
-                Item::List(l) => {
-                    s.push_str(format!("{l}, ").as_str());
-                }
+                Item::Num(n) => write!(f, "{n}")?,
+                Item::List(l) => l.fmt_pretty(f, depth + 1)?,
             }
+            writeln!(f, "{}", if i + 1 < self.0.len() { "," } else { "" })?;
         }
-        s.push(']');
-        write!(f, "{s}")
+        write!(f, "{:indent$}]", "", indent = 2 * depth)
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum Token {
-    OpenBracket,
-    CloseBracket,
-    Comma,
+/// Writes items in the compact form, brackets included. Works on a slice so a promoted
+/// integer (`std::slice::from_ref`) can be printed as a list without building one.
+fn write_compact<W: std::fmt::Write>(items: &[Item], w: &mut W) -> std::fmt::Result {
+    write!(w, "[")?;
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(w, ",")?;
+        }
+        match item {
+            Item::Num(n) => write!(w, "{n}")?,
+            Item::List(l) => write_compact(&l.0, w)?,
+        }
+    }
+    write!(w, "]")
 }
 
-impl Token {
-    fn next_token(s: &str) -> Result<(Option<u32>, Token, &str), &'static str> {
-        println!("next_token s: {s}");
-        let result = s.find(|c| c == '[' || c == ']' || c == ',');
-        let token_idx = if result.is_none() {
-            return Err("No tokens left in string");
+/// A random packet with up to `max_depth` levels of nesting, for property tests and benchmarks.
+pub fn random_packet<R: Rng>(rng: &mut R, max_depth: usize) -> List {
+    let len = rng.gen_range(0..=5);
+    List((0..len).map(|_| {
+        if max_depth > 0 && rng.gen_bool(0.3) {
+            Item::List(random_packet(rng, max_depth - 1))
         } else {
-            result.unwrap()
-        };
-        let token = match s.chars().nth(token_idx).unwrap() {
-            '[' => Ok(Token::OpenBracket),
-            ']' => Ok(Token::CloseBracket),
-            ',' => Ok(Token::Comma),
-            _ => {
-                Err("Couldn't find valid token in given str")
-            }
-        }?;
-        
-        let num = if token_idx != 0 {
-            let n = s[..token_idx].parse::<u32>().unwrap();
-            Some(n)
-        } else {
-            None
-        };
+            Item::Num(if rng.gen_bool(0.9) { rng.gen_range(0..=10) } else { rng.gen() })
+        }
+    }).collect())
+}
 
-        Ok((num, token, s.get(token_idx + 1..).unwrap()))
-    }
+/// Reads every packet in the file, skipping the blank lines between pairs.
+pub fn read_packets(path: &'static str) -> Vec<List> {
+    super::lines(path)
+        .map(|line| line.unwrap())
+        .filter(|line| !line.trim().is_empty())
+        .map(List::new)
+        .collect()
+}
+
+/// Part 1: sum of the (1-based) indices of the pairs that are in the right order.
+pub fn ordered_pair_sum<P: Ord>(packets: &[P]) -> usize {
+    packets.chunks(2)
+        .enumerate()
+        .filter(|(_, pair)| pair.len() == 2 && pair[0] < pair[1])
+        .map(|(i, _)| i + 1)
+        .sum()
+}
+
+/// Part 2: sort every packet together with the divider packets `[[2]]` and `[[6]]`,
+/// then multiply the (1-based) positions of the dividers.
+pub fn decoder_key(packets: &[List]) -> usize {
+    let dividers = [List::new("[[2]]".to_string()), List::new("[[6]]".to_string())];
+    let mut sorted: Vec<&List> = packets.iter().chain(dividers.iter()).collect();
+    sorted.sort();
+    dividers.iter()
+        .map(|divider| sorted.iter().position(|packet| std::ptr::eq(*packet, divider)).unwrap() + 1)
+        .product()
 }
 
 #[cfg(test)]
@@ -132,47 +170,118 @@ mod day_13 {
     use super::*;
 
     #[test]
-    fn next_token() {
+    fn test_parser() {
         let s = "[[0,1],[],3]";
+        let list = List::new(s.to_string());
+        println!("{:?}", list);
+        assert_eq!(list.0.len(), 3);
 
-        let (num, token, s) = Token::next_token(&s).unwrap();
-        assert!(num == None && token == Token::OpenBracket && s == "[0,1],[],3]");
-        
-        let (num, token, s) = Token::next_token(&s).unwrap();
-        assert!(num == None && token == Token::OpenBracket && s == "0,1],[],3]");
-        
-        let (num, token, s) = Token::next_token(&s).unwrap();
-        assert!(num == Some(0) && token == Token::Comma && s == "1],[],3]");
-        
-        let (num, token, s) = Token::next_token(&s).unwrap();
-        assert!(num == Some(1) && token == Token::CloseBracket && s == ",[],3]");
-        
-        let (num, token, s) = Token::next_token(&s).unwrap();
-        assert!(num == None && token == Token::Comma && s == "[],3]");
-        
-        let (num, token, s) = Token::next_token(&s).unwrap();
-        assert!(num == None && token == Token::OpenBracket && s == "],3]");
-        
-        let (num, token, s) = Token::next_token(&s).unwrap();
-        assert!(num == None && token == Token::CloseBracket && s == ",3]");
-        
-        let (num, token, s) = Token::next_token(&s).unwrap();
-        assert!(num == None && token == Token::Comma && s == "3]");
-        
-        let (num, token, s) = Token::next_token(&s).unwrap();
-        assert!(num == Some(3) && token == Token::CloseBracket && s == "");
+        let s = "[[[]]]";
+        let list = List::new(s.to_string());
+        println!("{:?}", list);
+        assert_eq!(list.to_json(), s);
+    }
 
+    /// The packet's own '[' opens the packet, not a nested list inside it: "[1,[2]]" is a
+    /// list of two items, not a list holding one list of two items.
+    #[test]
+    fn test_outer_bracket() {
+        let list = List::new("[1,[2],[]]".to_string());
+        assert_eq!(list, List(vec![
+            Item::Num(1),
+            Item::List(List(vec![Item::Num(2)])),
+            Item::List(List(vec![])),
+        ]));
+        assert_eq!(List::new("[]".to_string()), List(vec![]));
     }
 
     #[test]
-    fn test_parser() {
-        let s = "[[0,1],[],3]";
-        let list = List::new_list(s);
-        println!("{:?}", list);
+    #[should_panic(expected = "trailing comma at byte 2")]
+    fn test_new_panics() {
+        List::new("[1,]".to_string());
+    }
 
-        let s = "[[[]]]";
-        let list = List::new_list(s);
-        println!("{:?}", list);
+    const EXAMPLE: [(&str, &str, bool); 8] = [
+        ("[1,1,3,1,1]", "[1,1,5,1,1]", true),
+        ("[[1],[2,3,4]]", "[[1],4]", true),
+        ("[9]", "[[8,7,6]]", false),
+        ("[[4,4],4,4]", "[[4,4],4,4,4]", true),
+        ("[7,7,7,7]", "[7,7,7]", false),
+        ("[]", "[3]", true),
+        ("[[[]]]", "[[]]", false),
+        ("[1,[2,[3,[4,[5,6,7]]]],8,9]", "[1,[2,[3,[4,[5,6,0]]]],8,9]", false),
+    ];
+
+    fn example_packets() -> Vec<List> {
+        EXAMPLE.iter()
+            .flat_map(|(left, right, _)| [List::new(left.to_string()), List::new(right.to_string())])
+            .collect()
+    }
+
+    #[test]
+    fn test_ord() {
+        for (left, right, ordered) in EXAMPLE {
+            let left = List::new(left.to_string());
+            let right = List::new(right.to_string());
+            assert_eq!(left < right, ordered);
+            assert_eq!(left > right, !ordered);
+            assert_eq!(packets_are_ordered(&left, &right), Some(ordered));
+        }
+
+        assert_eq!(List::new("[1]".to_string()), List::new("[[1]]".to_string()));
+        assert_eq!(List::new("[[2]]".to_string()).cmp(&List::new("[2]".to_string())), Ordering::Equal);
+        assert!(List::new("[[]]".to_string()) < List::new("[0]".to_string()));
+    }
+
+    #[test]
+    fn test_solver() {
+        let packets = example_packets();
+        assert_eq!(ordered_pair_sum(&packets), 13);
+        assert_eq!(decoder_key(&packets), 140);
+
+        let packets = read_packets("inputs/13.inputs.txt");
+        assert_eq!(packets.len(), 16);
+        assert_eq!(ordered_pair_sum(&packets), 13);
+        assert_eq!(decoder_key(&packets), 140);
+    }
+
+    #[test]
+    fn test_display() {
+        let list = List::new("[1,[2,[]],[[3]],[]]".to_string());
+        assert_eq!(list.to_string(), "[1,[2,[]],[[3]],[]]");
+        assert_eq!(format!("{list:#}"), "\
+[
+  1,
+  [
+    2,
+    []
+  ],
+  [
+    [
+      3
+    ]
+  ],
+  []
+]");
+        assert_eq!(format!("{:#}", List::new("[]".to_string())), "[]");
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(38);
+        for _ in 0..2_000 {
+            let list = random_packet(&mut rng, 5);
+            let compact = list.to_string();
+            assert_eq!(compact, list.to_json());
+            assert_eq!(List::new(compact.clone()).to_string(), compact);
+
+            let pretty = format!("{list:#}");
+            let parsed = List::new(pretty.clone());
+            assert_eq!(parsed.to_string(), compact, "{pretty}");
+            assert_eq!(format!("{parsed:#}"), pretty);
+        }
     }
 
     #[test]
@@ -0,0 +1,236 @@
+/// Key concepts:
+/// * Recursive descent: one function per grammar rule, each calling the others.
+///   `list = '[' [ item { ',' item } ] ']'`, `item = list | number`,
+///   `number = '0' | '1'..'9' { '0'..'9' }`
+/// * ASCII whitespace is allowed between tokens (but not inside numbers), so the pretty
+///   `{:#}` form of a List parses back to the same packet.
+/// * Working on &[u8] with an index instead of slicing &str, so nothing is copied or allocated
+///   except the Vecs of the tree itself, and every error knows its byte position.
+use std::fmt::Display;
+use std::str::FromStr;
+use super::{Item, List};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The input ended inside a list (unbalanced brackets) or was empty.
+    UnexpectedEnd,
+    /// A byte that can't start or continue anything here.
+    UnexpectedByte(u8),
+    /// A ',' directly followed by ']'.
+    TrailingComma,
+    /// A number that doesn't fit in a u32.
+    Overflow,
+    /// A number like `07`. Rejected so every packet has exactly one spelling.
+    LeadingZero,
+    /// Anything after the packet's closing ']', e.g. an extra ']'.
+    TrailingInput,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset into the input where the problem was found.
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl std::error::Error for ParseError {}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of packet at byte {}", self.position),
+            ParseErrorKind::UnexpectedByte(b) => write!(f, "unexpected {:?} at byte {}", char::from(b), self.position),
+            ParseErrorKind::TrailingComma => write!(f, "trailing comma at byte {}", self.position),
+            ParseErrorKind::Overflow => write!(f, "number at byte {} doesn't fit in a u32", self.position),
+            ParseErrorKind::LeadingZero => write!(f, "number at byte {} has a leading zero", self.position),
+            ParseErrorKind::TrailingInput => write!(f, "unexpected input after the packet at byte {}", self.position),
+        }
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError { position: self.position, kind }
+    }
+
+    /// Error for whatever is at the current position: either the end of input or a bad byte.
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            None => self.error(ParseErrorKind::UnexpectedEnd),
+            Some(b) => self.error(ParseErrorKind::UnexpectedByte(b)),
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn list(&mut self) -> Result<List, ParseError> {
+        self.expect(b'[')?;
+        self.skip_whitespace();
+        let mut v: Vec<Item> = Vec::new();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(List(v));
+        }
+
+        loop {
+            v.push(self.item()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(List(v));
+                }
+                Some(b',') => {
+                    let comma = self.position;
+                    self.position += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b']') {
+                        return Err(ParseError { position: comma, kind: ParseErrorKind::TrailingComma });
+                    }
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn item(&mut self) -> Result<Item, ParseError> {
+        match self.peek() {
+            Some(b'[') => Ok(Item::List(self.list()?)),
+            Some(b'0'..=b'9') => Ok(Item::Num(self.number()?)),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn number(&mut self) -> Result<u32, ParseError> {
+        let start = self.position;
+        let mut n: u32 = 0;
+        while let Some(b @ b'0'..=b'9') = self.peek() {
+            if self.position > start && n == 0 {
+                return Err(ParseError { position: start, kind: ParseErrorKind::LeadingZero });
+            }
+            n = n.checked_mul(10)
+                .and_then(|n| n.checked_add((b - b'0') as u32))
+                .ok_or(ParseError { position: start, kind: ParseErrorKind::Overflow })?;
+            self.position += 1;
+        }
+        Ok(n)
+    }
+}
+
+impl List {
+    /// Parses a single packet such as `[1,[2,3],[]]`. The whole input must be one packet,
+    /// optionally surrounded by whitespace.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser { bytes: s.as_bytes(), position: 0 };
+        parser.skip_whitespace();
+        let list = parser.list()?;
+        parser.skip_whitespace();
+        if parser.position != parser.bytes.len() {
+            return Err(parser.error(ParseErrorKind::TrailingInput));
+        }
+        Ok(list)
+    }
+}
+
+impl FromStr for List {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        List::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod parser_13_tests {
+    use super::*;
+    use super::super::random_packet;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn error(s: &str) -> (usize, ParseErrorKind) {
+        let e = List::parse(s).unwrap_err();
+        (e.position, e.kind)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(List::parse("[[0,1],[],3]").unwrap().to_json(), "[[0,1],[],3]");
+        assert_eq!(List::parse("[]").unwrap().to_json(), "[]");
+        assert_eq!(List::parse("[[[]]]").unwrap().to_json(), "[[[]]]");
+        assert_eq!(List::parse("[0,10,4294967295]").unwrap().to_json(), "[0,10,4294967295]");
+        assert_eq!("[1,[2]]".parse::<List>(), List::parse("[1,[2]]"));
+        assert_eq!(List::parse(" [ 1 ,\n\t[2] ] \n").unwrap().to_json(), "[1,[2]]");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(error(""), (0, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("[1,2"), (4, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("[[1],"), (5, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("[1,]"), (2, ParseErrorKind::TrailingComma));
+        assert_eq!(error("[,1]"), (1, ParseErrorKind::UnexpectedByte(b',')));
+        assert_eq!(error("[1,a]"), (3, ParseErrorKind::UnexpectedByte(b'a')));
+        assert_eq!(error("[1 2]"), (3, ParseErrorKind::UnexpectedByte(b'2')));
+        assert_eq!(error("[1, ]"), (2, ParseErrorKind::TrailingComma));
+        assert_eq!(error("[1[2]]"), (2, ParseErrorKind::UnexpectedByte(b'[')));
+        assert_eq!(error("7"), (0, ParseErrorKind::UnexpectedByte(b'7')));
+        assert_eq!(error("[1]]"), (3, ParseErrorKind::TrailingInput));
+        assert_eq!(error("[1][2]"), (3, ParseErrorKind::TrailingInput));
+        assert_eq!(error("[3,01]"), (3, ParseErrorKind::LeadingZero));
+        assert_eq!(error("[4294967296]"), (1, ParseErrorKind::Overflow));
+        assert_eq!(List::parse("[1,]").unwrap_err().to_string(), "trailing comma at byte 2");
+    }
+
+    /// Randomly deletes, inserts or replaces a few bytes.
+    fn mutate(rng: &mut StdRng, s: &str) -> String {
+        const ALPHABET: &[u8] = b"[],0123456789";
+        let mut bytes = s.as_bytes().to_vec();
+        for _ in 0..rng.gen_range(1..=3) {
+            let i = rng.gen_range(0..=bytes.len());
+            let b = ALPHABET[rng.gen_range(0..ALPHABET.len())];
+            match rng.gen_range(0..3) {
+                0 if i < bytes.len() => { bytes.remove(i); }
+                1 if i < bytes.len() => bytes[i] = b,
+                _ => bytes.insert(i, b),
+            }
+        }
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn fuzz_against_serde() {
+        let mut rng = StdRng::seed_from_u64(13);
+        for _ in 0..20_000 {
+            let valid = random_packet(&mut rng, 4).to_json();
+            let s = if rng.gen_bool(0.2) { valid } else { mutate(&mut rng, &valid) };
+
+            let ours = List::parse(&s).map(|list| list.to_json());
+            let serde = List::from_json(&s).map(|list| list.to_json());
+            assert_eq!(ours.is_ok(), serde.is_ok(), "{s:?}: {ours:?} vs {serde:?}");
+            if let (Ok(ours), Ok(serde)) = (ours, serde) {
+                assert_eq!(ours, serde);
+                assert_eq!(ours, s);
+            }
+        }
+    }
+}
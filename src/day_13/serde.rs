@@ -1,5 +1,5 @@
-use serde::{Serialize, ser::SerializeSeq};
-use serde_json::Value;
+use serde::{Deserialize, Serialize, ser::SerializeSeq, de::{self, SeqAccess, Visitor}};
+use super::{Item, List};
 
 #[derive(Debug)]
 enum Items {
@@ -23,6 +23,44 @@ impl Serialize for Items {
     }
 }
 
+/// Accepts a non-negative integer that fits in a u32, or an array of `Items`.
+struct ItemsVisitor;
+
+impl<'de> Visitor<'de> for ItemsVisitor {
+    type Value = Items;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "an integer between 0 and {} or a list", u32::MAX)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        u32::try_from(v)
+            .map(Items::Num)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u32::try_from(v)
+            .map(Items::Num)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(element) = seq.next_element()? {
+            v.push(element);
+        }
+        Ok(Items::Inner(v))
+    }
+}
+
+impl<'de> Deserialize<'de> for Items {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: serde::Deserializer<'de> {
+        deserializer.deserialize_any(ItemsVisitor)
+    }
+}
+
 #[derive(Debug)]
 struct Packet(Vec<Items>);
 
@@ -35,4 +73,88 @@ impl Serialize for Packet {
         }
         seq.end()
     }
-}
\ No newline at end of file
+}
+
+/// A packet is always a list at the top level, so a bare integer is rejected.
+impl<'de> Deserialize<'de> for Packet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: serde::Deserializer<'de> {
+        Ok(Packet(Vec::<Items>::deserialize(deserializer)?))
+    }
+}
+
+impl From<Items> for Item {
+    fn from(items: Items) -> Self {
+        match items {
+            Items::Num(n) => Item::Num(n),
+            Items::Inner(v) => Item::List(List(v.into_iter().map(Item::from).collect())),
+        }
+    }
+}
+
+impl From<&Item> for Items {
+    fn from(item: &Item) -> Self {
+        match item {
+            Item::Num(n) => Items::Num(*n),
+            Item::List(l) => Items::Inner(l.0.iter().map(Items::from).collect()),
+        }
+    }
+}
+
+impl From<Packet> for List {
+    fn from(packet: Packet) -> Self {
+        List(packet.0.into_iter().map(Item::from).collect())
+    }
+}
+
+impl From<&List> for Packet {
+    fn from(list: &List) -> Self {
+        Packet(list.0.iter().map(Items::from).collect())
+    }
+}
+
+impl List {
+    /// Parses a packet with serde_json instead of the hand-written parser.
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str::<Packet>(s).map(List::from)
+    }
+
+    /// The packet as compact JSON, which is also the puzzle's input format.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&Packet::from(self)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod serde_13_tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json() {
+        let list = List::from_json("[[0,1],[],3]").unwrap();
+        assert_eq!(format!("{list:?}"), format!("{:?}", List::new("[[0,1],[],3]".to_string())));
+
+        assert!(List::from_json("3").is_err());
+        assert!(List::from_json("[-1]").is_err());
+        assert!(List::from_json("[4294967296]").is_err());
+        assert!(List::from_json("[1.5]").is_err());
+        assert!(List::from_json("[\"1\"]").is_err());
+        assert!(List::from_json("[1,]").is_err());
+        assert_eq!(List::from_json("[4294967295]").unwrap().to_json(), "[4294967295]");
+    }
+
+    #[test]
+    fn test_matches_parser() {
+        for line in crate::lines("inputs/13.inputs.txt") {
+            let line = line.unwrap();
+            if line.is_empty() {
+                continue;
+            }
+            let serde = List::from_json(&line).unwrap();
+            let parsed = List::new(line.clone());
+            assert_eq!(format!("{serde:?}"), format!("{parsed:?}"), "{line}");
+            assert_eq!(serde.to_json(), line);
+            assert_eq!(parsed.to_json(), line);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::fmt::Display;
+use super::{CdToken, Filesystem, FolderId};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsError {
+    /// Nothing at this path.
+    NotFound(String),
+    /// The destination name is already taken in its folder.
+    AlreadyExists(String),
+    /// / can't be removed or moved.
+    IsRoot,
+    /// Removing this folder would leave pwd inside a deleted folder.
+    Busy(String),
+    /// A folder can't be moved into itself or one of its subfolders.
+    IntoItself(String),
+}
+
+impl std::error::Error for FsError {}
+impl Display for FsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FsError::NotFound(path) => write!(f, "{path:?}: no such file or folder"),
+            FsError::AlreadyExists(path) => write!(f, "{path:?} already exists"),
+            FsError::IsRoot => write!(f, "can't remove or move /"),
+            FsError::Busy(path) => write!(f, "{path:?} contains the working folder"),
+            FsError::IntoItself(path) => write!(f, "can't move {path:?} into itself"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Entry {
+    Folder(FolderId),
+    /// Folder and index into its files.
+    File(FolderId, usize),
+}
+
+/// Splits a path into the folder part and the last name: "a/b/c" -> ("a/b/", "c"),
+/// "/c" -> ("/", "c"), "c" -> ("", "c"). A trailing '/' is ignored.
+fn split_last(path: &str) -> (&str, &str) {
+    let trimmed = path.trim_end_matches('/');
+    match trimmed.rfind('/') {
+        Some(i) => (&trimmed[..=i], &trimmed[i + 1..]),
+        None => ("", trimmed),
+    }
+}
+
+/// Names that refer to an existing folder rather than naming a new entry.
+fn is_special(name: &str) -> bool {
+    matches!(name, "" | "." | "..")
+}
+
+impl Filesystem {
+    /// Follows a folder path from pwd, without creating anything like `cd` does.
+    fn resolve_folder(&self, path: &str) -> Result<FolderId, FsError> {
+        let mut id = self.pwd();
+        for step in CdToken::path_steps(path) {
+            id = match step {
+                CdToken::Root => self.root(),
+                CdToken::Parent => self.folder(id).parent().unwrap_or(self.root()),
+                CdToken::Child(name) | CdToken::Path(name) => self.get_child(id, &name)
+                    .ok_or_else(|| FsError::NotFound(path.to_string()))?,
+            };
+        }
+        Ok(id)
+    }
+
+    fn resolve(&self, path: &str) -> Result<Entry, FsError> {
+        let (parent, name) = split_last(path);
+        if is_special(name) {
+            return self.resolve_folder(path).map(Entry::Folder);
+        }
+        let parent = self.resolve_folder(parent)?;
+        if let Some(id) = self.get_child(parent, &name) {
+            return Ok(Entry::Folder(id));
+        }
+        self.folder(parent).files
+            .iter()
+            .position(|f| f.name == name)
+            .map(|i| Entry::File(parent, i))
+            .ok_or_else(|| FsError::NotFound(path.to_string()))
+    }
+
+    fn name_taken(&self, folder: FolderId, name: &str) -> bool {
+        self.get_child(folder, &name).is_some() || self.folder(folder).files.iter().any(|f| f.name == name)
+    }
+
+    /// Whether `id` is `ancestor` or somewhere below it.
+    fn is_within(&self, id: FolderId, ancestor: FolderId) -> bool {
+        let mut next = Some(id);
+        while let Some(id) = next {
+            if id == ancestor {
+                return true;
+            }
+            next = self.folder(id).parent();
+        }
+        false
+    }
+
+    /// Creates an empty folder. The folder it goes in must already exist.
+    pub fn mkdir(&mut self, path: &str) -> Result<FolderId, FsError> {
+        let (parent, name) = split_last(path);
+        let parent = self.resolve_folder(parent)?;
+        if is_special(name) || self.name_taken(parent, name) {
+            return Err(FsError::AlreadyExists(path.to_string()));
+        }
+        Ok(self.create_child(parent, name))
+    }
+
+    /// Removes a file, or a folder and everything in it.
+    pub fn rm(&mut self, path: &str) -> Result<(), FsError> {
+        match self.resolve(path)? {
+            Entry::File(folder, i) => {
+                self.folder_mut(folder).files.remove(i);
+                self.invalidate(folder);
+            }
+            Entry::Folder(id) => {
+                let parent = self.folder(id).parent().ok_or(FsError::IsRoot)?;
+                if self.is_within(self.pwd(), id) {
+                    return Err(FsError::Busy(path.to_string()));
+                }
+                self.folder_mut(parent).child_folders.retain(|child| *child != id);
+                self.invalidate(parent);
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves or renames a file or folder. If `to` is an existing folder, the entry moves
+    /// into it and keeps its name; otherwise `to` is its new path.
+    pub fn mv(&mut self, from: &str, to: &str) -> Result<(), FsError> {
+        let entry = self.resolve(from)?;
+        let name = match entry {
+            Entry::Folder(id) if id == self.root() => return Err(FsError::IsRoot),
+            Entry::Folder(id) => self.folder(id).name().to_string(),
+            Entry::File(folder, i) => self.folder(folder).files[i].name.clone(),
+        };
+        let (dest, name) = match self.resolve_folder(to) {
+            Ok(dest) => (dest, name),
+            Err(_) => {
+                let (parent, name) = split_last(to);
+                (self.resolve_folder(parent)?, name.to_string())
+            }
+        };
+        if let Entry::Folder(id) = entry {
+            if self.is_within(dest, id) {
+                return Err(FsError::IntoItself(from.to_string()));
+            }
+        }
+        if self.name_taken(dest, &name) {
+            return Err(FsError::AlreadyExists(to.to_string()));
+        }
+
+        match entry {
+            Entry::File(folder, i) => {
+                let mut file = self.folder_mut(folder).files.remove(i);
+                self.invalidate(folder);
+                file.name = name;
+                self.folder_mut(dest).files.push(file);
+            }
+            Entry::Folder(id) => {
+                let parent = self.folder(id).parent().expect("/ was rejected above");
+                self.folder_mut(parent).child_folders.retain(|child| *child != id);
+                self.invalidate(parent);
+                self.folder_mut(dest).child_folders.push(id);
+                let folder = self.folder_mut(id);
+                folder.parent_folder = Some(dest);
+                folder.name = name;
+            }
+        }
+        self.invalidate(dest);
+        Ok(())
+    }
+
+}
+
+#[cfg(test)]
+mod commands_tests {
+    use super::*;
+    use super::super::tests_7::EXAMPLE;
+
+    fn example() -> Filesystem {
+        Filesystem::from_transcript(EXAMPLE.lines().map(String::from))
+    }
+
+    fn pwd(fs: &Filesystem) -> String {
+        fs.qualified_name(fs.folder(fs.pwd()))
+    }
+
+    #[test]
+    fn test_split_last() {
+        assert_eq!(split_last("a/b/c"), ("a/b/", "c"));
+        assert_eq!(split_last("/c/"), ("/", "c"));
+        assert_eq!(split_last("c"), ("", "c"));
+        assert_eq!(split_last("/"), ("", ""));
+    }
+
+    #[test]
+    fn test_cd_path() {
+        let mut fs = example();
+        fs.cd(CdToken::Path("/a/e/../../d"));
+        assert_eq!(pwd(&fs), "/d/");
+        fs.cd(CdToken::Path("../a/./e/"));
+        assert_eq!(pwd(&fs), "/a/e/");
+        fs.cd(CdToken::Path("/"));
+        assert_eq!(pwd(&fs), "/");
+        fs.cd(CdToken::Path("../x/y"));
+        assert_eq!(pwd(&fs), "/x/y/");
+    }
+
+    #[test]
+    fn test_mkdir() {
+        let mut fs = example();
+        let id = fs.mkdir("/a/new").unwrap();
+        assert_eq!(fs.qualified_name(fs.folder(id)), "/a/new/");
+        assert_eq!(fs.total_size(), None);
+        assert_eq!(fs.mkdir("/a/new"), Err(FsError::AlreadyExists("/a/new".to_string())));
+        assert_eq!(fs.mkdir("/a/f"), Err(FsError::AlreadyExists("/a/f".to_string())));
+        assert_eq!(fs.mkdir("/nope/new"), Err(FsError::NotFound("/nope/".to_string())));
+        assert_eq!(fs.mkdir("/"), Err(FsError::AlreadyExists("/".to_string())));
+
+        fs.update_sizes();
+        assert_eq!(fs.size_of("/a/new/"), Some(0));
+        assert_eq!(fs.total_size(), Some(48381165));
+    }
+
+    #[test]
+    fn test_rm() {
+        let mut fs = example();
+        fs.rm("/a/e/i").unwrap();
+        assert_eq!(fs.size_of("/a/e/"), None);
+        assert_eq!(fs.size_of("/a/"), None);
+        assert_eq!(fs.total_size(), None);
+        assert_eq!(fs.size_of("/d/"), Some(24933642));
+        fs.update_sizes();
+        assert_eq!(fs.size_of("/a/"), Some(94853 - 584));
+
+        fs.cd(CdToken::Path("/a/e"));
+        assert_eq!(fs.rm("/a"), Err(FsError::Busy("/a".to_string())));
+        assert_eq!(fs.rm(".."), Err(FsError::Busy("..".to_string())));
+        assert_eq!(fs.rm("/"), Err(FsError::IsRoot));
+        assert_eq!(fs.rm("/a/x"), Err(FsError::NotFound("/a/x".to_string())));
+
+        fs.cd(CdToken::Root::<&str>);
+        fs.rm("d").unwrap();
+        fs.update_sizes();
+        assert_eq!(fs.size_of("/d/"), None);
+        assert_eq!(fs.total_size(), Some(48381165 - 584 - 24933642));
+        assert_eq!(fs.folders().count(), 3);
+    }
+
+    #[test]
+    fn test_mv() {
+        let mut fs = example();
+        fs.mv("/b.txt", "/a/e/b2.txt").unwrap();
+        assert_eq!(fs.total_size(), None);
+        fs.update_sizes();
+        assert_eq!(fs.size_of("/a/e/"), Some(584 + 14848514));
+        assert_eq!(fs.total_size(), Some(48381165));
+
+        fs.mv("/a", "/d").unwrap();
+        assert_eq!(fs.size_of("/a/"), None);
+        fs.update_sizes();
+        assert_eq!(fs.size_of("/d/a/e/"), Some(584 + 14848514));
+        assert_eq!(fs.size_of("/d/"), Some(24933642 + 94853 + 14848514));
+
+        fs.mv("/d/a", "/z").unwrap();
+        fs.update_sizes();
+        let names: Vec<String> = fs.folders().map(|f| fs.qualified_name(f)).collect();
+        assert_eq!(names, ["/", "/d/", "/z/", "/z/e/"]);
+
+        assert_eq!(fs.mv("/z", "/z/e"), Err(FsError::IntoItself("/z".to_string())));
+        assert_eq!(fs.mv("/z", "/z"), Err(FsError::IntoItself("/z".to_string())));
+        assert_eq!(fs.mv("/c.dat", "/d/j"), Err(FsError::AlreadyExists("/d/j".to_string())));
+        assert_eq!(fs.mv("/", "/d"), Err(FsError::IsRoot));
+        assert_eq!(fs.mv("/nope", "/d"), Err(FsError::NotFound("/nope".to_string())));
+        assert_eq!(fs.mv("/c.dat", "/nope/x"), Err(FsError::NotFound("/nope/".to_string())));
+    }
+}
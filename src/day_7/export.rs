@@ -0,0 +1,162 @@
+use std::io;
+use std::path::Path;
+use serde::{de::Error, Deserialize, Serialize};
+use super::{CdToken, File, Filesystem, Folder, LsToken};
+
+/// One folder in the JSON export. Folders are listed flat, parents before children, so
+/// importing never recurses and deep trees don't hit serde_json's nesting limit.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct FolderRecord {
+    /// Qualified name, e.g. "/a/e/".
+    path: String,
+    /// Informational; sizes are recomputed on import.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    #[serde(default)]
+    files: Vec<File>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Snapshot {
+    folders: Vec<FolderRecord>,
+}
+
+/// Folder and file names have to be usable as a single path component on disk.
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+impl Filesystem {
+    /// Pretty-printed JSON: `{"folders": [{"path": "/", "size": .., "files": [..]}, ..]}`.
+    pub fn to_json(&self) -> String {
+        let mut folders = Vec::new();
+        self.walk_folders(|folder, path, _| folders.push(FolderRecord {
+            path: path.to_string(),
+            size: folder.size(),
+            files: folder.files().to_vec(),
+        }));
+        serde_json::to_string_pretty(&Snapshot { folders }).unwrap()
+    }
+
+    /// Rebuilds a filesystem from `to_json` output and computes its sizes. Parent folders
+    /// that aren't listed are created empty.
+    pub fn from_json(s: &str) -> serde_json::Result<Filesystem> {
+        let snapshot: Snapshot = serde_json::from_str(s)?;
+        let mut fs = Filesystem::new();
+        for record in snapshot.folders {
+            let segments = record.path
+                .strip_prefix('/')
+                .and_then(|p| if p.is_empty() { Some(p) } else { p.strip_suffix('/') })
+                .ok_or_else(|| serde_json::Error::custom(format!("folder path {:?} must start and end with '/'", record.path)))?;
+
+            fs.cd(CdToken::Root::<String>);
+            for segment in segments.split('/').filter(|s| !s.is_empty()) {
+                if !valid_name(segment) {
+                    return Err(serde_json::Error::custom(format!("invalid folder name {segment:?} in {:?}", record.path)));
+                }
+                fs.cd(CdToken::Child(segment));
+            }
+            for file in record.files {
+                if !valid_name(&file.name) {
+                    return Err(serde_json::Error::custom(format!("invalid file name {:?} in {:?}", file.name, record.path)));
+                }
+                fs.ls_once(LsToken::File(file.name, file.size));
+            }
+        }
+        fs.cd(CdToken::Root::<String>);
+        fs.update_sizes();
+        Ok(fs)
+    }
+
+    /// Recreates the tree under `dir`, which must exist. Files are created sparse with
+    /// `set_len`, so their recorded sizes take no real disk space on most filesystems.
+    pub fn materialize<P: AsRef<Path>>(&self, dir: P) -> io::Result<()> {
+        let dir = dir.as_ref();
+        let mut result = Ok(());
+        self.walk_folders(|folder, qualified_name, _| {
+            if result.is_ok() {
+                result = Self::materialize_folder(dir, folder, qualified_name);
+            }
+        });
+        result
+    }
+
+    fn materialize_folder(dir: &Path, folder: &Folder, qualified_name: &str) -> io::Result<()> {
+        let relative = qualified_name.trim_start_matches('/');
+        if !relative.split('/').filter(|s| !s.is_empty()).all(valid_name) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("can't create folder {qualified_name:?}")));
+        }
+        let path = dir.join(relative);
+        std::fs::create_dir_all(&path)?;
+        for file in folder.files() {
+            if !valid_name(&file.name) {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("can't create file {:?}", file.name)));
+            }
+            std::fs::File::create(path.join(&file.name))?.set_len(file.size)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+    use super::super::tests_7::EXAMPLE;
+
+    fn example() -> Filesystem {
+        Filesystem::from_transcript(EXAMPLE.lines().map(String::from))
+    }
+
+    fn listing(fs: &Filesystem) -> Vec<(String, Option<u64>, Vec<File>)> {
+        fs.preorder()
+            .into_iter()
+            .map(|(f, _)| (fs.qualified_name(f), f.size(), f.files().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json: serde_json::Value = serde_json::from_str(&example().to_json()).unwrap();
+        assert_eq!(json["folders"][0]["path"], "/");
+        assert_eq!(json["folders"][0]["size"], 48381165);
+        assert_eq!(json["folders"][1]["path"], "/a/");
+        assert_eq!(json["folders"][2]["files"][0], serde_json::json!({"name": "i", "size": 584}));
+        assert_eq!(json["folders"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = std::fs::read_to_string("inputs/7.inputs.txt").unwrap();
+        for transcript in [EXAMPLE, input.as_str()] {
+            let fs = Filesystem::from_transcript(transcript.lines().map(String::from));
+            let imported = Filesystem::from_json(&fs.to_json()).unwrap();
+            assert_eq!(listing(&imported), listing(&fs));
+            assert_eq!(imported.to_json(), fs.to_json());
+        }
+    }
+
+    #[test]
+    fn test_from_json() {
+        let fs = Filesystem::from_json(r#"{"folders": [{"path": "/x/y/", "files": [{"name": "f", "size": 5}]}]}"#).unwrap();
+        assert_eq!(fs.size_of("/x/"), Some(5));
+        assert_eq!(fs.total_size(), Some(5));
+
+        assert!(Filesystem::from_json(r#"{"folders": [{"path": "x/"}]}"#).is_err());
+        assert!(Filesystem::from_json(r#"{"folders": [{"path": "/x"}]}"#).is_err());
+        assert!(Filesystem::from_json(r#"{"folders": [{"path": "/../"}]}"#).is_err());
+        assert!(Filesystem::from_json(r#"{"folders": [{"path": "/", "files": [{"name": "..", "size": 1}]}]}"#).is_err());
+        assert!(Filesystem::from_json(r#"{"folders": [{"path": "/", "files": [{"name": "a"}]}]}"#).is_err());
+        assert!(Filesystem::from_json("[]").is_err());
+    }
+
+    #[test]
+    fn test_deep_round_trip() {
+        let mut fs = Filesystem::new();
+        for _ in 0..1_000 {
+            fs.cd(CdToken::Child("d"));
+        }
+        fs.ls_once(LsToken::File("f", 1));
+        fs.update_sizes();
+        assert_eq!(Filesystem::from_json(&fs.to_json()).unwrap().total_size(), Some(1));
+    }
+}
@@ -0,0 +1,425 @@
+use std::fmt::Display;
+use super::{CdToken, Disk, LsToken};
+
+/// Index of a folder in its Filesystem. Only meaningful for the Filesystem that created it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FolderId(usize);
+
+const ROOT: FolderId = FolderId(0);
+
+/// Folders live in one Vec owned by the Filesystem and refer to each other by FolderId,
+/// so there are no Rc cycles and no RefCell borrows.
+#[derive(Debug)]
+pub struct Filesystem {
+    folders: Vec<Folder>,
+    pwd: FolderId,
+}
+
+impl Filesystem {
+    /// Creates new filesystem with root folder (pwd = root)
+    pub fn new() -> Self {
+        Filesystem {
+            folders: vec![Folder::root()],
+            pwd: ROOT, }
+    }
+
+    pub fn root(&self) -> FolderId {
+        ROOT
+    }
+
+    pub fn pwd(&self) -> FolderId {
+        self.pwd
+    }
+
+    pub fn folder(&self, id: FolderId) -> &Folder {
+        &self.folders[id.0]
+    }
+
+    pub(super) fn folder_mut(&mut self, id: FolderId) -> &mut Folder {
+        &mut self.folders[id.0]
+    }
+
+    /// Creates a new child folder under `parent`
+    pub(super) fn create_child<T: ToString>(&mut self, parent: FolderId, name: T) -> FolderId {
+        let id = FolderId(self.folders.len());
+        let child = Folder::new(name.to_string(), parent);
+        self.folders.push(child);
+        self.folder_mut(parent).add_child_folder(id);
+        self.invalidate(parent);
+        id
+    }
+
+    /// Forgets the size of `id` and of every folder above it. A folder without a size never
+    /// has an ancestor with one, so this can stop at the first folder that's already unsized.
+    pub(super) fn invalidate(&mut self, id: FolderId) {
+        self.folder_mut(id).size = None;
+        let mut next = self.folder(id).parent_folder;
+        while let Some(parent) = next {
+            let folder = self.folder_mut(parent);
+            if folder.size.is_none() {
+                break;
+            }
+            folder.size = None;
+            next = folder.parent_folder;
+        }
+    }
+
+    pub(super) fn get_child<T: ToString>(&self, parent: FolderId, name: &T) -> Option<FolderId> {
+        let name = name.to_string();
+        self.folder(parent).child_folders
+            .iter()
+            .copied()
+            .find(|child| self.folder(*child).name == name)
+    }
+
+    /// Sets pwd to token.
+    /// If CdToken::Child(s) is supplied, s === name of child folder.
+    /// CdToken::Path(s) is any path, e.g. "/a/b/../c" or "b/c", taken one step at a time.
+    /// Creates child folders if necessary.
+    pub fn cd<T: ToString>(&mut self, token: CdToken<T>) {
+        match token {
+            CdToken::Root => {
+                self.pwd = ROOT
+            },
+            CdToken::Parent => {
+                self.pwd = self.folder(self.pwd).parent_folder.unwrap_or(ROOT);
+            },
+            CdToken::Child(name) => {
+                if let Some(c) = self.get_child(self.pwd, &name) {
+                    self.pwd = c;
+                } else {
+                    self.pwd = self.create_child(self.pwd, name.to_string());
+                }
+            },
+            CdToken::Path(path) => {
+                for step in CdToken::path_steps(&path.to_string()) {
+                    self.cd(step);
+                }
+            },
+        }
+    }
+
+    /// Adds supplied Dir or File under pwd, if it doesn't already exist.
+    pub fn ls_once<T: ToString>(&mut self, token: LsToken<T>) {
+        match token {
+            LsToken::Dir(s) => {
+                if self.get_child(self.pwd, &s).is_none() {
+                    self.create_child(self.pwd, s);
+                }
+            }
+            LsToken::File(s, n) => {
+                if !self.folder(self.pwd).exists_file(&s) {
+                    self.folder_mut(self.pwd).add_file(s, n);
+                    self.invalidate(self.pwd);
+                }
+            }
+        }
+    }
+
+    /// Computes every folder's size in one post-order pass: a folder is summed when it is
+    /// popped the second time, after all of its children. Uses an explicit stack instead of
+    /// recursion, so arbitrarily deep trees are fine. Doesn't move pwd.
+    pub fn update_sizes(&mut self) {
+        let mut stack = vec![(ROOT, false)];
+        while let Some((id, children_done)) = stack.pop() {
+            if children_done {
+                let folder = self.folder(id);
+                let files_size: u64 = folder.files.iter().map(|f| f.size).sum();
+                let folders_size: u64 = folder.child_folders
+                    .iter()
+                    .map(|child| self.folder(*child).size.expect("children are sized first"))
+                    .sum();
+                self.folder_mut(id).size = Some(files_size + folders_size);
+            } else {
+                stack.push((id, true));
+                stack.extend(self.folder(id).child_folders.iter().map(|child| (*child, false)));
+            }
+        }
+    }
+
+    /// Part 1: total size of the folders under 100000.
+    pub fn small_folder_sizes(&self) -> u64 {
+        self.folders_where(|size| size < 100000)
+            .filter_map(Folder::size)
+            .sum()
+    }
+
+    /// Part 2: size of the smallest folder that frees enough space for the update.
+    pub fn calc_delete(&self) -> u64 {
+        self.smallest_to_free(Disk::PUZZLE)
+            .and_then(Folder::size)
+            .expect("the puzzle disk is big enough for the update")
+    }
+
+    /// Size of everything under /. None until update_sizes has run.
+    pub fn total_size(&self) -> Option<u64> {
+        self.folder(ROOT).size
+    }
+
+    /// Size of the folder with the given qualified name, e.g. "/a/e/".
+    pub fn size_of(&self, qualified_name: &str) -> Option<u64> {
+        let path = qualified_name.strip_prefix('/')?;
+        let mut id = ROOT;
+        if !path.is_empty() {
+            for name in path.strip_suffix('/')?.split('/') {
+                id = self.get_child(id, &name)?;
+            }
+        }
+        self.folder(id).size
+    }
+
+    /// Full path ending in '/', e.g. "/a/e/". Folders only store their own name, so this
+    /// walks up to / and takes time proportional to the folder's depth.
+    pub fn qualified_name(&self, folder: &Folder) -> String {
+        let mut names = Vec::new();
+        let mut next = Some(folder);
+        while let Some(folder) = next {
+            next = folder.parent_folder.map(|parent| self.folder(parent));
+            if next.is_some() {
+                names.push(folder.name.as_str());
+            }
+        }
+        let mut path = String::from("/");
+        for name in names.iter().rev() {
+            path.push_str(name);
+            path.push('/');
+        }
+        path
+    }
+
+    /// Every folder still in the tree, in `preorder`. Removed folders stay in the arena
+    /// until the Filesystem is dropped, but are no longer reachable from /.
+    pub fn folders(&self) -> impl Iterator<Item = &Folder> {
+        self.preorder().into_iter().map(|(folder, _)| folder)
+    }
+
+    /// Every folder with its depth (/ is 0), depth first with parents before children.
+    pub fn preorder(&self) -> Vec<(&Folder, usize)> {
+        let mut order = Vec::with_capacity(self.folders.len());
+        let mut stack = vec![(ROOT, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            let folder = self.folder(id);
+            order.push((folder, depth));
+            stack.extend(folder.child_folders.iter().rev().map(|child| (*child, depth + 1)));
+        }
+        order
+    }
+
+    /// Visits every folder in `preorder` with its qualified name, depth and size.
+    pub fn walk<F: FnMut(&str, usize, Option<u64>)>(&self, mut f: F) {
+        self.walk_folders(|folder, path, depth| f(path, depth, folder.size));
+    }
+
+    /// Visits every folder in `preorder` with its qualified name and depth. The name is built
+    /// up and cut back as the walk goes down and up, so deep trees don't cost a full path each.
+    pub(super) fn walk_folders<F: FnMut(&Folder, &str, usize)>(&self, mut f: F) {
+        let mut path = String::new();
+        // Length of `path` up to and including the folder at each depth.
+        let mut ends: Vec<usize> = Vec::new();
+        for (folder, depth) in self.preorder() {
+            ends.truncate(depth);
+            path.truncate(ends.last().copied().unwrap_or(0));
+            if depth > 0 {
+                path.push_str(&folder.name);
+            }
+            path.push('/');
+            ends.push(path.len());
+            f(folder, &path, depth);
+        }
+    }
+}
+
+impl Default for Filesystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Folder {
+    pub(super) name: String,
+    pub(super) parent_folder: Option<FolderId>,
+    pub(super) child_folders: Vec<FolderId>,
+    pub(super) files: Vec<File>,
+    size: Option<u64>,
+}
+
+impl Folder {
+    fn root() -> Self {
+        Folder {
+            name: String::from(" "),
+            ..Default::default()
+        }
+    }
+
+    fn new<T: ToString>(name: T, parent_id: FolderId) -> Self {
+        Folder {
+            name: name.to_string(),
+            parent_folder: Some(parent_id),
+            ..Default::default()
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// None for the root folder.
+    pub fn parent(&self) -> Option<FolderId> {
+        self.parent_folder
+    }
+
+    pub fn children(&self) -> &[FolderId] {
+        &self.child_folders
+    }
+
+    pub fn files(&self) -> &[File] {
+        &self.files
+    }
+
+    /// None until Filesystem::update_sizes has run, and again after the folder changes.
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    fn add_child_folder(&mut self, child: FolderId) {
+        self.child_folders.push(child);
+        self.size = None;
+    }
+
+    fn add_file<T: ToString>(&mut self, name: T, size: u64) {
+        self.files.push(File { name: name.to_string(), size});
+        self.size = None;
+    }
+
+    fn exists_file<T: ToString>(&self, name: &T) -> bool {
+        let name = name.to_string();
+        self.files.iter().any(|file| file.name == name)
+    }
+}
+
+/// `name size`, or `name ?` before the size is known. For the full path, see
+/// `Filesystem::qualified_name`.
+impl Display for Folder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(n) = self.size {
+            write!(f, "{} {}", self.name.as_str(), n)
+        } else {
+            write!(f, "{} ?", self.name.as_str())
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct File {
+    pub name: String,
+    pub size: u64,
+}
+
+#[cfg(test)]
+mod filesystem_tests {
+    use super::*;
+
+    #[test]
+    fn test_filesystem_mkdir() {
+        let mut fs = Filesystem::new();
+        let child = fs.create_child(ROOT, "child1");
+        assert_eq!(fs.get_child(ROOT, &"child1"), Some(child));
+        assert_eq!(fs.folder(child).parent(), Some(fs.pwd()));
+        assert_eq!(fs.qualified_name(fs.folder(child)), "/child1/");
+        assert_eq!(fs.qualified_name(fs.folder(ROOT)), "/");
+        assert_eq!(fs.folder(ROOT).children(), [child]);
+        println!("Display: {}", fs.folder(child));
+        println!("Debug: {:?}", fs.folder(child));
+    }
+
+    #[test]
+    fn test_filesystem_cd() {
+        let mut fs = Filesystem::new();
+        fs.cd(CdToken::Child("child1"));
+
+        assert!(fs.get_child(ROOT, &"child1").is_some());
+        assert_eq!(fs.folder(fs.pwd()).name(), "child1");
+        assert_eq!(fs.folder(fs.pwd()).parent(), Some(ROOT));
+
+        fs.cd(CdToken::Parent::<String>);
+        assert_eq!(fs.pwd(), ROOT);
+        fs.cd(CdToken::Parent::<String>);
+        assert_eq!(fs.pwd(), ROOT);
+
+        let child2 = fs.create_child(ROOT, "child2");
+        assert_eq!(fs.folder(ROOT).children().len(), 2);
+        fs.cd(CdToken::Child("child2"));
+        assert_eq!(fs.folder(ROOT).children().len(), 2);
+        assert_eq!(fs.pwd(), child2);
+
+        fs.cd(CdToken::Root::<String>);
+        assert_eq!(fs.pwd(), ROOT);
+    }
+
+    #[test]
+    fn test_filesystem_ls_once() {
+        let mut fs = Filesystem::new();
+        fs.ls_once(LsToken::File("file1", 3));
+        fs.ls_once(LsToken::File("file1", 5));
+        fs.ls_once(LsToken::Dir("child1"));
+        fs.ls_once(LsToken::Dir("child1"));
+        assert_eq!(fs.pwd(), ROOT);
+        assert_eq!(fs.folder(ROOT).children().len(), 1);
+        assert_eq!(fs.folder(ROOT).files(), [File { name: "file1".to_string(), size: 3 }]);
+    }
+
+    #[test]
+    fn test_filesystem_update_sizes() {
+        let mut fs = Filesystem::new();
+        fs.ls_once(LsToken::File("file1", 10));     // File under root
+        fs.ls_once(LsToken::File("file2", 10));
+        fs.ls_once(LsToken::File("file3", 10));
+
+        fs.cd(CdToken::Child("child1"));
+        let child1 = fs.pwd();
+        fs.ls_once(LsToken::File("file3", 100));    // File under child1
+        fs.ls_once(LsToken::File("file4", 100));
+        fs.ls_once(LsToken::File("file5", 100));
+        assert_eq!(fs.total_size(), None);
+
+        fs.update_sizes();
+        assert_eq!(fs.pwd(), child1);
+        assert_eq!(fs.total_size(), Some(330));
+        assert_eq!(fs.folder(child1).size(), Some(300));
+        assert_eq!(fs.folders_where(|size| size < 100000).count(), 2);
+
+        fs.ls_once(LsToken::File("big", 5_000_000_000));
+        assert_eq!(fs.folder(child1).size(), None);
+        assert_eq!(fs.total_size(), None);
+        fs.update_sizes();
+        assert_eq!(fs.total_size(), Some(5_000_000_330));
+        assert_eq!(fs.folders_where(|size| size < 100000).count(), 0);
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        const DEPTH: usize = 20_000;
+        let mut fs = Filesystem::new();
+        for _ in 0..DEPTH {
+            fs.cd(CdToken::Child("d"));
+            fs.ls_once(LsToken::File("f", 1));
+        }
+        fs.update_sizes();
+
+        assert_eq!(fs.total_size(), Some(DEPTH as u64));
+        assert_eq!(fs.folder(fs.pwd()).size(), Some(1));
+        let mut deepest = (0, 0);
+        fs.walk(|name, depth, _| deepest = deepest.max((depth, name.len())));
+        assert_eq!(deepest, (DEPTH, 2 * DEPTH + 1));
+        assert_eq!(fs.qualified_name(fs.folder(fs.pwd())).len(), 2 * DEPTH + 1);
+    }
+
+    #[test]
+    fn test_input_sizes() {
+        let fs = Filesystem::read("inputs/7.inputs.txt");
+        assert_eq!(fs.folders().count(), 190);
+        assert_eq!(fs.total_size(), Some(42586708));
+        assert_eq!(fs.folders_where(|_| true).count(), fs.folders().count());
+    }
+}
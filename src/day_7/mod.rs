@@ -0,0 +1,203 @@
+/// Key topics covered:
+/// * The borrow checker
+/// * Rc, RefCell, the Rc<RefCell<...>> pattern, and why parent pointers make it leak
+/// * Arena trees: folders in a Vec, parent/children as indices (see filesystem.rs)
+/// * Returning `impl Iterator` so queries can be chained with the usual adapters
+/// * serde derives on a flat, non-recursive export format, and std::fs (see export.rs)
+/// * Keeping cached sizes honest when the tree changes: invalidate up to / (see commands.rs)
+/// * let-else, and drawing a tree with an explicit stack of line prefixes (see render.rs)
+/// - impl Display for T...
+///
+/// ```text
+///  ____[[Folder]]____
+/// | * name           |
+/// | * Vec<files>     |
+/// | * Vec<folders>   |
+/// | * size           |
+/// |__________________|
+/// ```
+mod filesystem;
+mod commands;
+mod export;
+mod query;
+mod transcript;
+mod render;
+
+pub use commands::FsError;
+pub use filesystem::{File, Filesystem, Folder, FolderId};
+pub use query::{glob_match, Disk};
+pub use render::{human_size, TreeOptions};
+pub use transcript::{Issue, Mode, TranscriptError};
+
+pub enum CdToken<T> {
+    Root,
+    Parent,
+    Child(T),
+    /// Absolute or relative, any number of segments: "/a/b/../c", "b/c", "./d".
+    Path(T),
+}
+
+impl<'a> CdToken<&'a str> {
+    /// The single steps a path stands for: Root if it is absolute, then one Parent or
+    /// Child per segment. Empty and "." segments are skipped.
+    pub fn path_steps(path: &'a str) -> Vec<Self> {
+        let mut steps = Vec::new();
+        if path.starts_with('/') {
+            steps.push(CdToken::Root);
+        }
+        for segment in path.split('/') {
+            match segment {
+                "" | "." => continue,
+                ".." => steps.push(CdToken::Parent),
+                name => steps.push(CdToken::Child(name)),
+            }
+        }
+        steps
+    }
+}
+
+pub enum LsToken<T> {
+    Dir(T),
+    File(T, u64),
+}
+
+/// One line of the terminal transcript.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Token {
+    Cd(String),
+    Ls,
+    Dir(String),
+    File(u64, String),
+    Mkdir(String),
+    Rm(String),
+    Mv(String, String),
+    Error
+}
+
+impl Token {
+    /// Parses one line. Anything that isn't a well-formed command or listing is Token::Error.
+    pub fn parse_string(s: String) -> Self {
+        let mut tokens = s.split_whitespace();
+        match tokens.next() {
+            None => Self::Error,
+            Some("dir") => tokens.next().map_or(Self::Error, |d| Self::Dir(d.to_string())),
+            Some("$") => {
+                match tokens.next() {
+                    Some("ls") => Self::Ls,
+                    Some("cd") => tokens.next().map_or(Self::Error, |d| Self::Cd(d.to_string())),
+                    Some("mkdir") => tokens.next().map_or(Self::Error, |d| Self::Mkdir(d.to_string())),
+                    Some("rm") => tokens.next().map_or(Self::Error, |p| Self::Rm(p.to_string())),
+                    Some("mv") => match (tokens.next(), tokens.next()) {
+                        (Some(from), Some(to)) => Self::Mv(from.to_string(), to.to_string()),
+                        _ => Self::Error,
+                    },
+                    _ => Self::Error,
+                }
+            }
+            Some(n) => match (n.parse::<u64>(), tokens.next()) {
+                (Ok(n), Some(f)) => Self::File(n, f.to_string()),
+                _ => Self::Error,
+            }
+        }
+    }
+}
+
+impl Filesystem {
+    /// Replays a terminal transcript (`$ cd ..`, `$ ls`, `dir a`, `123 b.txt`) and computes
+    /// every folder's size. Lenient: see `Mode::Lenient` for what gets glossed over.
+    pub fn from_transcript<I: IntoIterator<Item = String>>(lines: I) -> Self {
+        Self::parse_transcript(lines, Mode::Lenient).expect("lenient parsing never fails")
+    }
+
+    /// Reads a transcript from a file. See `from_transcript`.
+    pub fn read<T: ToString>(path: T) -> Self {
+        Self::from_transcript(super::lines(path).map(|line| line.unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests_7 {
+    use super::*;
+
+    pub(super) const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    fn example() -> Filesystem {
+        Filesystem::from_transcript(EXAMPLE.lines().map(String::from))
+    }
+
+    #[test]
+    fn test_token_parse_string() {
+        let t = Token::parse_string("$ cd /".into());
+        assert_eq!(t, Token::Cd("/".to_owned()));
+        
+        let t = Token::parse_string("$ ls".into());
+        assert_eq!(t, Token::Ls);
+
+        let t = Token::parse_string("dir bfqzjjct".into());
+        assert_eq!(t, Token::Dir(String::from("bfqzjjct")));
+
+        let t = Token::parse_string("293559 jztrccm.hvd".into());
+        assert_eq!(t, Token::File(293559, String::from("jztrccm.hvd")));
+
+        let t = Token::parse_string("$ mv a/b /c".into());
+        assert_eq!(t, Token::Mv("a/b".to_owned(), "/c".to_owned()));
+
+        for malformed in ["", "dir", "$ cd", "$ rm", "$ mv a", "$ cp a b", "12x a", "123", "-5 a"] {
+            assert_eq!(Token::parse_string(malformed.into()), Token::Error, "{malformed:?}");
+        }
+    }
+
+    #[test]
+    fn test_from_transcript() {
+        let fs = example();
+        assert_eq!(fs.total_size(), Some(48381165));
+        assert_eq!(fs.size_of("/a/"), Some(94853));
+        assert_eq!(fs.size_of("/a/e/"), Some(584));
+        assert_eq!(fs.size_of("/d/"), Some(24933642));
+        assert_eq!(fs.size_of("/x/"), None);
+        assert_eq!(fs.small_folder_sizes(), 95437);
+        assert_eq!(fs.calc_delete(), 24933642);
+    }
+
+    #[test]
+    fn test_walk() {
+        let mut folders = Vec::new();
+        example().walk(|name, depth, size| folders.push((name.to_string(), depth, size.unwrap())));
+        assert_eq!(folders, [
+            ("/".to_string(), 0, 48381165),
+            ("/a/".to_string(), 1, 94853),
+            ("/a/e/".to_string(), 2, 584),
+            ("/d/".to_string(), 1, 24933642),
+        ]);
+    }
+
+    #[test]
+    fn test_input() {
+        let fs = Filesystem::read("inputs/7.inputs.txt");
+        assert_eq!(fs.small_folder_sizes(), 1453349);
+        assert_eq!(fs.calc_delete(), 2948823);
+    }
+}
@@ -0,0 +1,158 @@
+use std::cmp::Reverse;
+use super::{Filesystem, Folder};
+
+/// Disk capacity and the free space an update needs, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disk {
+    pub total: u64,
+    pub required: u64,
+}
+
+impl Disk {
+    /// The device from the puzzle: 70000000 bytes, and the update needs 30000000 free.
+    pub const PUZZLE: Disk = Disk { total: 70000000, required: 30000000 };
+}
+
+impl Filesystem {
+    /// Folders whose size satisfies `predicate`. Folders without a size yet are skipped.
+    pub fn folders_where<P: Fn(u64) -> bool>(&self, predicate: P) -> impl Iterator<Item = &Folder> {
+        self.folders().filter(move |f| f.size().is_some_and(&predicate))
+    }
+
+    /// Folders whose qualified name matches a glob pattern. See `glob_match`.
+    pub fn glob<'a>(&'a self, pattern: &'a str) -> impl Iterator<Item = &'a Folder> {
+        self.folders().filter(move |f| glob_match(pattern, &self.qualified_name(f)))
+    }
+
+    /// The `n` largest folders, biggest first. Ties are broken by qualified name.
+    pub fn largest(&self, n: usize) -> Vec<&Folder> {
+        let mut folders: Vec<&Folder> = self.folders_where(|_| true).collect();
+        folders.sort_by_cached_key(|f| (Reverse(f.size()), self.qualified_name(f)));
+        folders.truncate(n);
+        folders
+    }
+
+    /// The smallest folder that, once deleted, leaves at least `disk.required` bytes free.
+    /// None if no folder is big enough, which can only happen if the update doesn't fit
+    /// on an empty disk either.
+    pub fn smallest_to_free(&self, disk: Disk) -> Option<&Folder> {
+        let used = self.total_size()?;
+        let missing = disk.required.saturating_sub(disk.total.saturating_sub(used));
+        self.folders_where(|size| size >= missing)
+            .min_by_key(|f| (f.size(), self.qualified_name(f)))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Pattern {
+    Byte(u8),
+    /// `?`: one character other than '/'.
+    Any,
+    /// `*`: any run of characters other than '/'.
+    Star,
+    /// `**`: any run of characters, '/' included.
+    DoubleStar,
+}
+
+/// Shell-style glob over a qualified name like "/a/e/". `?` and `*` stay within one path
+/// segment and `**` crosses segments, so "/*/" matches top-level folders and "**/e/"
+/// matches every folder named e.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut tokens = Vec::new();
+    let mut bytes = pattern.bytes().peekable();
+    while let Some(b) = bytes.next() {
+        tokens.push(match b {
+            b'?' => Pattern::Any,
+            b'*' if bytes.peek() == Some(&b'*') => {
+                bytes.next();
+                Pattern::DoubleStar
+            }
+            b'*' => Pattern::Star,
+            b => Pattern::Byte(b),
+        });
+    }
+
+    // matches[j]: does the pattern from the current token on match text[j..]?
+    // Built from the last token backwards, so the whole match is O(pattern * text).
+    let text = text.as_bytes();
+    let mut matches = vec![false; text.len() + 1];
+    matches[text.len()] = true;
+    for token in tokens.iter().rev() {
+        let next = matches.clone();
+        for j in (0..=text.len()).rev() {
+            let c = text.get(j).copied();
+            matches[j] = match token {
+                Pattern::Byte(b) => c == Some(*b) && next[j + 1],
+                Pattern::Any => c.is_some_and(|c| c != b'/') && next[j + 1],
+                Pattern::Star => next[j] || (c.is_some_and(|c| c != b'/') && matches[j + 1]),
+                Pattern::DoubleStar => next[j] || (c.is_some() && matches[j + 1]),
+            };
+        }
+    }
+    matches[0]
+}
+
+#[cfg(test)]
+mod query_tests {
+    use super::*;
+    use super::super::tests_7::EXAMPLE;
+
+    fn example() -> Filesystem {
+        Filesystem::from_transcript(EXAMPLE.lines().map(String::from))
+    }
+
+    fn names<'a, I: IntoIterator<Item = &'a Folder>>(fs: &Filesystem, folders: I) -> Vec<String> {
+        folders.into_iter().map(|f| fs.qualified_name(f)).collect()
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("/", "/"));
+        assert!(glob_match("/*/", "/a/"));
+        assert!(!glob_match("/*/", "/a/e/"));
+        assert!(glob_match("/**/", "/a/e/"));
+        assert!(glob_match("**/e/", "/a/e/"));
+        assert!(glob_match("/?/", "/d/"));
+        assert!(!glob_match("/?/", "/dd/"));
+        assert!(glob_match("/a*/", "/a/"));
+        assert!(glob_match("/*a*b/", "/xaxxb/"));
+        assert!(!glob_match("/a", "/a/"));
+        assert!(!glob_match("", "/"));
+    }
+
+    #[test]
+    fn test_folders_where() {
+        let fs = example();
+        assert_eq!(names(&fs, fs.folders_where(|size| size <= 100000)), ["/a/", "/a/e/"]);
+        assert_eq!(fs.folders_where(|size| size <= 100000).filter_map(Folder::size).sum::<u64>(), 95437);
+        assert_eq!(names(&fs, fs.folders_where(|size| size > 10_000_000)), ["/", "/d/"]);
+        assert_eq!(Filesystem::new().folders_where(|_| true).count(), 0);
+    }
+
+    #[test]
+    fn test_glob() {
+        let fs = example();
+        assert_eq!(names(&fs, fs.glob("/*/")), ["/a/", "/d/"]);
+        assert_eq!(names(&fs, fs.glob("**/e/")), ["/a/e/"]);
+        assert_eq!(names(&fs, fs.glob("/a/**")), ["/a/", "/a/e/"]);
+    }
+
+    #[test]
+    fn test_largest() {
+        let fs = example();
+        assert_eq!(names(&fs, fs.largest(2)), ["/", "/d/"]);
+        assert_eq!(names(&fs, fs.largest(10)), ["/", "/d/", "/a/", "/a/e/"]);
+        assert!(fs.largest(0).is_empty());
+    }
+
+    #[test]
+    fn test_smallest_to_free() {
+        let fs = example();
+        assert_eq!(names(&fs, fs.smallest_to_free(Disk::PUZZLE)), ["/d/"]);
+        // 100000000 - 48381165 is already free, so anything will do.
+        assert_eq!(names(&fs, fs.smallest_to_free(Disk { total: 100_000_000, required: 30_000_000 })), ["/a/e/"]);
+        // Only deleting everything makes room.
+        assert_eq!(names(&fs, fs.smallest_to_free(Disk { total: 50_000_000, required: 50_000_000 })), ["/"]);
+        assert_eq!(fs.smallest_to_free(Disk { total: 50_000_000, required: 60_000_000 }), None);
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt::{Display, Write};
+use super::{File, Filesystem, FolderId};
+
+/// How `Filesystem::render_tree` lays out the listing. The default shows everything in
+/// transcript order, folders before files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TreeOptions {
+    /// Deepest level to show; / is 0. Sizes still include everything below it.
+    pub max_depth: Option<usize>,
+    /// Biggest entries first instead of transcript order.
+    pub sort_by_size: bool,
+    /// Leave out files, like `du`.
+    pub dirs_only: bool,
+}
+
+/// Formats a byte count the way `du -h` does: plain bytes below 1K, otherwise 1024-based
+/// units rounded up, with one decimal below 10.
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [char; 6] = ['K', 'M', 'G', 'T', 'P', 'E'];
+    if bytes < 1024 {
+        return bytes.to_string();
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    loop {
+        let rounded = if value < 10.0 { (value * 10.0).ceil() / 10.0 } else { value.ceil() };
+        if rounded >= 1024.0 && unit + 1 < UNITS.len() {
+            value /= 1024.0;
+            unit += 1;
+        } else if rounded < 10.0 {
+            return format!("{rounded:.1}{}", UNITS[unit]);
+        } else {
+            return format!("{rounded:.0}{}", UNITS[unit]);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Entry<'a> {
+    Folder(FolderId),
+    File(&'a File),
+}
+
+impl Filesystem {
+    fn entry_size(&self, entry: Entry) -> Option<u64> {
+        match entry {
+            Entry::Folder(id) => self.folder(id).size(),
+            Entry::File(file) => Some(file.size),
+        }
+    }
+
+    fn entry_name<'a>(&'a self, entry: Entry<'a>) -> &'a str {
+        match entry {
+            Entry::Folder(id) if id == self.root() => "/",
+            Entry::Folder(id) => self.folder(id).name(),
+            Entry::File(file) => &file.name,
+        }
+    }
+
+    fn entries(&self, id: FolderId, options: TreeOptions) -> Vec<Entry<'_>> {
+        let folder = self.folder(id);
+        let mut entries: Vec<Entry> = folder.children().iter().map(|child| Entry::Folder(*child)).collect();
+        if !options.dirs_only {
+            entries.extend(folder.files().iter().map(Entry::File));
+        }
+        if options.sort_by_size {
+            entries.sort_by_key(|e| std::cmp::Reverse(self.entry_size(*e)));
+        }
+        entries
+    }
+
+    /// Renders the tree like `tree -h`, one entry per line with its size, e.g.
+    /// `├── [ 93K]  a`. Unsized folders show `?`. Uses an explicit stack, so deep
+    /// trees are fine.
+    pub fn render_tree(&self, options: TreeOptions) -> String {
+        let mut out = String::new();
+        // (entry, depth, what goes before its name, what goes before its children's names)
+        let mut stack = vec![(Entry::Folder(self.root()), 0, String::new(), String::new())];
+        while let Some((entry, depth, line_prefix, child_prefix)) = stack.pop() {
+            let size = self.entry_size(entry).map_or("?".to_string(), human_size);
+            writeln!(out, "{line_prefix}[{size:>4}]  {}", self.entry_name(entry)).unwrap();
+
+            let Entry::Folder(id) = entry else { continue };
+            if options.max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            let entries = self.entries(id, options);
+            let last = entries.len().saturating_sub(1);
+            for (i, child) in entries.into_iter().enumerate().rev() {
+                let (branch, continuation) = if i == last { ("└── ", "    ") } else { ("├── ", "│   ") };
+                stack.push((child, depth + 1, format!("{child_prefix}{branch}"), format!("{child_prefix}{continuation}")));
+            }
+        }
+        out
+    }
+}
+
+/// Same as `render_tree` with the default options.
+impl Display for Filesystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render_tree(TreeOptions::default()))
+    }
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+    use super::super::tests_7::EXAMPLE;
+    use super::super::CdToken;
+
+    fn example() -> Filesystem {
+        Filesystem::from_transcript(EXAMPLE.lines().map(String::from))
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(0), "0");
+        assert_eq!(human_size(1023), "1023");
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(1025), "1.1K");
+        assert_eq!(human_size(10 * 1024), "10K");
+        assert_eq!(human_size(10 * 1024 - 1), "10K");
+        assert_eq!(human_size(1024 * 1024 - 1), "1.0M");
+        assert_eq!(human_size(48381165), "47M");
+        assert_eq!(human_size(u64::MAX), "16E");
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let fs = example();
+        assert_eq!(fs.to_string(), "\
+[ 47M]  /
+├── [ 93K]  a
+│   ├── [ 584]  e
+│   │   └── [ 584]  i
+│   ├── [ 29K]  f
+│   ├── [2.5K]  g
+│   └── [ 62K]  h.lst
+├── [ 24M]  d
+│   ├── [3.9M]  j
+│   ├── [7.7M]  d.log
+│   ├── [5.4M]  d.ext
+│   └── [6.9M]  k
+├── [ 15M]  b.txt
+└── [8.2M]  c.dat
+");
+    }
+
+    #[test]
+    fn test_render_options() {
+        let fs = example();
+        let options = TreeOptions { max_depth: Some(1), sort_by_size: true, dirs_only: false };
+        assert_eq!(fs.render_tree(options), "\
+[ 47M]  /
+├── [ 24M]  d
+├── [ 15M]  b.txt
+├── [8.2M]  c.dat
+└── [ 93K]  a
+");
+
+        let options = TreeOptions { max_depth: None, sort_by_size: true, dirs_only: true };
+        assert_eq!(fs.render_tree(options), "\
+[ 47M]  /
+├── [ 24M]  d
+└── [ 93K]  a
+    └── [ 584]  e
+");
+
+        let mut fs = Filesystem::new();
+        fs.cd(CdToken::Child("a"));
+        assert_eq!(fs.to_string(), "[   ?]  /\n└── [   ?]  a\n");
+    }
+}
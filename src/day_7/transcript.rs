@@ -0,0 +1,208 @@
+use std::fmt::Display;
+use super::{CdToken, Filesystem, FsError, LsToken, Token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Report every inconsistency in the transcript.
+    Strict,
+    /// Today's behavior: malformed lines and failed commands are skipped, `cd ..` at / stays
+    /// at /, `cd` into an unlisted folder creates it, and the first size listed for a file wins.
+    Lenient,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// Not a command or listing line we understand.
+    Malformed(String),
+    /// `cd ..` while already at /.
+    CdAboveRoot,
+    /// `cd` into a folder no `ls` has listed.
+    CdUnlisted(String),
+    /// A file listed again with a different size.
+    ConflictingFile { name: String, listed: u64, now: u64 },
+    /// A `dir` or file line that doesn't follow `$ ls`.
+    OutputWithoutLs,
+    /// An `mkdir`, `rm` or `mv` that failed.
+    Command(FsError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptError {
+    /// 1-based line number in the transcript.
+    pub line: usize,
+    pub issue: Issue,
+}
+
+impl std::error::Error for TranscriptError {}
+impl Display for TranscriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.issue {
+            Issue::Malformed(line) => write!(f, "can't parse {line:?}"),
+            Issue::CdAboveRoot => write!(f, "cd .. at /"),
+            Issue::CdUnlisted(name) => write!(f, "cd into {name:?}, which no ls has listed"),
+            Issue::ConflictingFile { name, listed, now } => write!(f, "{name:?} was listed with size {listed}, now {now}"),
+            Issue::OutputWithoutLs => write!(f, "listing output without a preceding ls"),
+            Issue::Command(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Filesystem {
+    /// Replays a transcript like `from_transcript`. In strict mode, returns every
+    /// inconsistency found instead of the filesystem, if there are any.
+    pub fn parse_transcript<I: IntoIterator<Item = String>>(lines: I, mode: Mode) -> Result<Self, Vec<TranscriptError>> {
+        let mut fs = Filesystem::new();
+        let mut errors = Vec::new();
+        let mut in_ls = false;
+
+        for (i, line) in lines.into_iter().enumerate() {
+            let mut report = |issue| errors.push(TranscriptError { line: i + 1, issue });
+            match Token::parse_string(line.clone()) {
+                Token::Cd(d) => {
+                    in_ls = false;
+                    for step in CdToken::path_steps(&d) {
+                        match step {
+                            CdToken::Parent if fs.pwd() == fs.root() => report(Issue::CdAboveRoot),
+                            CdToken::Child(name) if fs.get_child(fs.pwd(), &name).is_none() => {
+                                report(Issue::CdUnlisted(name.to_string()))
+                            }
+                            _ => {}
+                        }
+                        fs.cd(step);
+                    }
+                }
+                Token::Ls => in_ls = true,
+                Token::Dir(d) => {
+                    if !in_ls {
+                        report(Issue::OutputWithoutLs);
+                    }
+                    fs.ls_once(LsToken::Dir(d))
+                }
+                Token::File(n, name) => {
+                    if !in_ls {
+                        report(Issue::OutputWithoutLs);
+                    }
+                    let listed = fs.folder(fs.pwd()).files().iter().find(|f| f.name == name).map(|f| f.size);
+                    if let Some(listed) = listed.filter(|listed| *listed != n) {
+                        report(Issue::ConflictingFile { name: name.clone(), listed, now: n });
+                    }
+                    fs.ls_once(LsToken::File(name, n))
+                }
+                Token::Mkdir(path) => {
+                    in_ls = false;
+                    if let Err(e) = fs.mkdir(&path) {
+                        report(Issue::Command(e));
+                    }
+                }
+                Token::Rm(path) => {
+                    in_ls = false;
+                    if let Err(e) = fs.rm(&path) {
+                        report(Issue::Command(e));
+                    }
+                }
+                Token::Mv(from, to) => {
+                    in_ls = false;
+                    if let Err(e) = fs.mv(&from, &to) {
+                        report(Issue::Command(e));
+                    }
+                }
+                Token::Error => report(Issue::Malformed(line)),
+            }
+        }
+
+        fs.update_sizes();
+        if mode == Mode::Strict && !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(fs)
+    }
+}
+
+#[cfg(test)]
+mod transcript_tests {
+    use super::*;
+    use super::super::tests_7::EXAMPLE;
+
+    fn parse(transcript: &str, mode: Mode) -> Result<Filesystem, Vec<TranscriptError>> {
+        Filesystem::parse_transcript(transcript.lines().map(String::from), mode)
+    }
+
+    #[test]
+    fn test_valid() {
+        let input = std::fs::read_to_string("inputs/7.inputs.txt").unwrap();
+        for transcript in [EXAMPLE, input.as_str()] {
+            let strict = parse(transcript, Mode::Strict).unwrap();
+            let lenient = parse(transcript, Mode::Lenient).unwrap();
+            assert_eq!(strict.to_json(), lenient.to_json());
+        }
+    }
+
+    #[test]
+    fn test_strict() {
+        let transcript = "\
+$ cd /
+$ cd ..
+$ ls
+dir a
+10 f
+10 f
+$ cd b
+20 g
+$ cd ..
+$ ls
+12 f
+$ frobnicate
+";
+        let errors = parse(transcript, Mode::Strict).unwrap_err();
+        assert_eq!(errors, [
+            TranscriptError { line: 2, issue: Issue::CdAboveRoot },
+            TranscriptError { line: 7, issue: Issue::CdUnlisted("b".to_string()) },
+            TranscriptError { line: 8, issue: Issue::OutputWithoutLs },
+            TranscriptError { line: 11, issue: Issue::ConflictingFile { name: "f".to_string(), listed: 10, now: 12 } },
+            TranscriptError { line: 12, issue: Issue::Malformed("$ frobnicate".to_string()) },
+        ]);
+        assert_eq!(errors[3].to_string(), "line 11: \"f\" was listed with size 10, now 12");
+
+        let fs = parse(transcript, Mode::Lenient).unwrap();
+        assert_eq!(fs.size_of("/b/"), Some(20));
+        assert_eq!(fs.total_size(), Some(30));
+    }
+
+    #[test]
+    fn test_commands() {
+        let transcript = "\
+$ cd /
+$ ls
+dir a
+10 f
+$ mkdir b
+$ cd b/../a
+$ ls
+20 g
+$ mv g /b/h
+$ cd /b
+$ ls
+30 i
+$ rm /f
+$ rm /f
+$ mv /a /b/a
+$ cd /b/a/../..
+$ cd ..
+";
+        let errors = parse(transcript, Mode::Strict).unwrap_err();
+        assert_eq!(errors, [
+            TranscriptError { line: 14, issue: Issue::Command(FsError::NotFound("/f".to_string())) },
+            TranscriptError { line: 17, issue: Issue::CdAboveRoot },
+        ]);
+        assert_eq!(errors[0].to_string(), "line 14: \"/f\": no such file or folder");
+
+        let fs = parse(transcript, Mode::Lenient).unwrap();
+        assert_eq!(fs.size_of("/b/"), Some(50));
+        assert_eq!(fs.size_of("/b/a/"), Some(0));
+        assert_eq!(fs.total_size(), Some(50));
+
+        let valid = transcript.lines().take(13).map(String::from);
+        assert!(Filesystem::parse_transcript(valid, Mode::Strict).is_ok());
+    }
+}
@@ -1,4 +1,14 @@
+/// Key concepts:
+/// * State machines with enums (StateMachine, kept only to check Rope against)
+/// * TryFrom and the Error trait
+/// * One general engine beats a hand-written transition table (see rope.rs)
+/// * Drawing onto a grid sized by a bounding box, and per-step callbacks for animation (see render.rs)
 pub mod part_2;
+mod render;
+mod rope;
+
+pub use render::{Bounds, RenderOptions};
+pub use rope::{Knot, Rope};
 
 use std::collections::HashSet;
 
@@ -23,6 +33,18 @@ enum MovH {
     Left,
 }
 
+impl MovH {
+    /// Unit step in (x, y), with y pointing up.
+    fn delta(self) -> (i32, i32) {
+        match self {
+            Self::Up => (0, 1),
+            Self::Right => (1, 0),
+            Self::Down => (0, -1),
+            Self::Left => (-1, 0),
+        }
+    }
+}
+
 impl TryFrom<char> for MovH {
     type Error = InvalidCharError;
 
@@ -46,11 +68,14 @@ impl std::fmt::Display for InvalidCharError {
     }
 }
 
+/// 2-knot rope as a transition table over where the head sits relative to the tail.
+#[deprecated(note = "use `Rope::new(2)`, which handles any number of knots")]
 pub struct StateMachine {
     abs_t: (isize, isize),      // Absolute position of tail
     rel_h: PosH,                // Relative position of head
     pub visited: HashSet<(isize, isize)>,   // Set of all previously visited locations
 }
+#[allow(deprecated)]
 impl StateMachine {
     pub fn new() -> Self {
         let mut visited = HashSet::new();
@@ -72,7 +97,6 @@ impl StateMachine {
         .try_into()
         .unwrap();
         let rep = t.last().unwrap().parse().unwrap();
-        println!("{:?}",&(dir, rep));
         (dir, rep)
     }
 
@@ -224,7 +248,35 @@ impl StateMachine {
 mod structs_9_test {
     use super::*;
 
+    pub(super) const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2";
+
+    pub(super) const LARGER_EXAMPLE: &str = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20";
+
+    /// A `num_knots` rope after every line of `input`.
+    pub(super) fn run(num_knots: usize, input: &str) -> Rope {
+        let mut rope = Rope::new(num_knots);
+        for line in input.lines() {
+            rope.step(line.to_string());
+        }
+        rope
+    }
+
     #[test]
+    #[allow(deprecated)]
     fn step() {
         let state = StateMachine::new();
     }
@@ -0,0 +1,220 @@
+use std::fmt::Display;
+use super::Rope;
+
+/// Inclusive rectangle of grid cells, y pointing up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: (i32, i32),
+    pub max: (i32, i32),
+}
+
+impl Bounds {
+    /// Smallest box holding the start and every point.
+    fn around<I: IntoIterator<Item = (i32, i32)>>(points: I) -> Self {
+        points.into_iter().fold(Bounds { min: (0, 0), max: (0, 0) }, |b, (x, y)| Bounds {
+            min: (b.min.0.min(x), b.min.1.min(y)),
+            max: (b.max.0.max(x), b.max.1.max(y)),
+        })
+    }
+
+    fn contains(&self, (x, y): (i32, i32)) -> bool {
+        (self.min.0..=self.max.0).contains(&x) && (self.min.1..=self.max.1).contains(&y)
+    }
+}
+
+/// What `Rope::render` draws. The start is always drawn as `s`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// The knots as `H`, `1`..`9` and `T`; a knot covers the ones behind it. Ropes longer
+    /// than 10 knots draw the extra knots between 9 and the tail as `+`.
+    pub knots: bool,
+    /// Every location the tail has visited, as `#`.
+    pub trail: bool,
+    /// Area to draw. None fits whatever is drawn; pass `Rope::extent` to keep a fixed
+    /// viewport across animation frames.
+    pub bounds: Option<Bounds>,
+}
+
+impl RenderOptions {
+    /// Just the rope, like the puzzle's step-by-step states.
+    pub const KNOTS: Self = Self { knots: true, trail: false, bounds: None };
+    /// Just the tail's trail, like the puzzle's final summary.
+    pub const TRAIL: Self = Self { knots: false, trail: true, bounds: None };
+}
+
+impl Rope {
+    /// Label drawn for knot `i`. Single digits run out after knot 9, so any later knots
+    /// other than the tail share `+` rather than wrapping round to `0`, `1`, ...
+    fn label(&self, i: usize) -> char {
+        match i {
+            0 => 'H',
+            i if i == self.num_knots() - 1 => 'T',
+            1..=9 => char::from_digit(i as u32, 10).unwrap(),
+            _ => '+',
+        }
+    }
+
+    /// Smallest box around what `options` would draw.
+    pub fn bounds(&self, options: RenderOptions) -> Bounds {
+        let knots = self.positions().filter(|_| options.knots);
+        let trail = self.visited().iter().copied().filter(|_| options.trail);
+        Bounds::around(knots.chain(trail))
+    }
+
+    /// Box around every location any knot has been.
+    pub fn extent(&self) -> Bounds {
+        Bounds::around((0..self.num_knots()).flat_map(|i| self.visited_by(i).iter().copied()))
+    }
+
+    /// Draws the grid the way the puzzle does: top row first, `.` for empty cells.
+    /// Panics if `options.bounds` is an empty box, i.e. `min` is past `max`.
+    pub fn render(&self, options: RenderOptions) -> String {
+        let bounds = options.bounds.unwrap_or_else(|| self.bounds(options));
+        assert!(bounds.min.0 <= bounds.max.0 && bounds.min.1 <= bounds.max.1, "empty bounds {bounds:?}");
+        let width = (bounds.max.0 - bounds.min.0 + 1) as usize;
+        let height = (bounds.max.1 - bounds.min.1 + 1) as usize;
+        let mut grid = vec![vec!['.'; width]; height];
+        let mut put = |(x, y): (i32, i32), c: char| {
+            if bounds.contains((x, y)) {
+                grid[(bounds.max.1 - y) as usize][(x - bounds.min.0) as usize] = c;
+            }
+        };
+
+        if options.trail {
+            for &position in self.visited() {
+                put(position, '#');
+            }
+        }
+        put((0, 0), 's');
+        if options.knots {
+            let positions: Vec<(i32, i32)> = self.positions().collect();
+            for (i, &position) in positions.iter().enumerate().rev() {
+                put(position, self.label(i));
+            }
+        }
+
+        let mut out = String::with_capacity((width + 1) * height);
+        for row in grid {
+            out.extend(row);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl Display for Rope {
+    /// Knots on top of the tail's trail.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(RenderOptions { knots: true, trail: true, bounds: None }))
+    }
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+    use super::super::structs_9_test::{run, EXAMPLE, LARGER_EXAMPLE};
+    const PUZZLE_BOX: Bounds = Bounds { min: (0, 0), max: (5, 4) };
+
+    #[test]
+    fn test_render_knots() {
+        let rope = run(2, EXAMPLE);
+        let options = RenderOptions { bounds: Some(PUZZLE_BOX), ..RenderOptions::KNOTS };
+        assert_eq!(rope.render(options), "\
+......
+......
+.TH...
+......
+s.....
+");
+
+        let rope = run(10, "R 5");
+        let options = RenderOptions { bounds: Some(Bounds { min: (-1, -1), max: (6, 1) }), ..RenderOptions::KNOTS };
+        assert_eq!(rope.render(options), "\
+........
+.54321H.
+........
+");
+    }
+
+    #[test]
+    fn test_render_trail() {
+        let rope = run(2, EXAMPLE);
+        assert_eq!(rope.bounds(RenderOptions::TRAIL), Bounds { min: (0, 0), max: (4, 4) });
+        let options = RenderOptions { bounds: Some(PUZZLE_BOX), ..RenderOptions::TRAIL };
+        assert_eq!(rope.render(options), "\
+..##..
+...##.
+.####.
+....#.
+s###..
+");
+
+        let rope = run(10, LARGER_EXAMPLE);
+        let options = RenderOptions { bounds: Some(Bounds { min: (-11, -5), max: (14, 15) }), ..RenderOptions::TRAIL };
+        assert_eq!(rope.render(options), "\
+..........................
+..........................
+..........................
+..........................
+..........................
+..........................
+..........................
+..........................
+..........................
+#.........................
+#.............###.........
+#............#...#........
+.#..........#.....#.......
+..#..........#.....#......
+...#........#.......#.....
+....#......s.........#....
+.....#..............#.....
+......#............#......
+.......#..........#.......
+........#........#........
+.........########.........
+");
+    }
+
+    #[test]
+    fn test_display() {
+        let rope = run(2, "R 2\nU 1");
+        assert_eq!(rope.to_string(), "\
+..H
+sT.
+");
+        assert_eq!(Rope::new(1).to_string(), "H\n");
+    }
+
+    #[test]
+    fn test_long_rope_labels() {
+        let rope = run(12, "R 12");
+        let options = RenderOptions { bounds: Some(Bounds { min: (0, 0), max: (12, 0) }), ..RenderOptions::KNOTS };
+        assert_eq!(rope.render(options), "sT+987654321H\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "empty bounds")]
+    fn test_render_empty_bounds() {
+        let options = RenderOptions { bounds: Some(Bounds { min: (1, 0), max: (0, 0) }), ..RenderOptions::KNOTS };
+        Rope::new(2).render(options);
+    }
+
+    #[test]
+    fn test_frames() {
+        let mut rope = Rope::new(3);
+        let bounds = Some(Bounds { min: (0, 0), max: (3, 0) });
+        let mut frames = Vec::new();
+        rope.step_with("R 3".to_string(), |rope| {
+            frames.push(rope.render(RenderOptions { bounds, ..RenderOptions::KNOTS }))
+        });
+        assert_eq!(frames, ["1H..\n", "T1H.\n", "sT1H\n"]);
+
+        let mut rope = run(10, LARGER_EXAMPLE);
+        let extent = rope.extent();
+        assert_eq!(extent, Bounds { min: (-11, -5), max: (14, 15) });
+        rope.step_with("D 30".to_string(), |rope| {
+            assert_eq!(rope.render(RenderOptions { bounds: Some(extent), ..RenderOptions::KNOTS }).lines().count(), 21)
+        });
+    }
+}
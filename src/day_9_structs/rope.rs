@@ -0,0 +1,305 @@
+/// Key concepts:
+/// * One engine for any number of knots: each knot just follows the one before it
+/// * A 2-knot rope is part 1, a 10-knot rope is part 2
+use std::collections::HashSet;
+use super::MovH;
+
+#[derive(Clone, Default, Debug)]
+pub struct Knot {
+    position: (i32, i32),
+}
+
+impl Knot {
+    pub fn new() -> Self {
+        Self {position: (0,0)}
+    }
+
+    fn calc_dxy(child_pos: (i32, i32), parent_pos: (i32, i32)) -> (i32, i32) {
+        let (self_x, self_y) = child_pos;
+        let (parent_x, parent_y) = parent_pos;
+        let dx = parent_x - self_x;
+        let dy = parent_y - self_y;
+        
+        if dx.abs() < 2 && dy.abs() < 2 {
+            return (0,0)
+        } 
+
+        let tail_dx = if dx.is_positive() {1} 
+            else if dx.is_negative() {-1} 
+            else {0};
+        let tail_dy = if dy.is_positive() {1} 
+            else if dy.is_negative() {-1} 
+            else {0};
+        (tail_dx, tail_dy)
+    }
+
+    /// Takes in a single step in the form of (x, y). Validates that 
+    /// step is of magnitude 1 [e.g. (1,0), (0,1), (-1,0), (0,-1)]
+    /// and shifts knot's position by that much. Returns updated 
+    /// knot position.
+    pub fn update_rel(&mut self, rel_pos: (i32, i32)) -> (i32, i32) {
+        let (dx, dy) = rel_pos;
+        assert!((-1..=1).contains(&dx));
+        assert!((-1..=1).contains(&dy));
+        self.position.0 += dx;
+        self.position.1 += dy;
+        (self.position.0, self.position.1)
+    }
+
+    /// Takes in the absolute position of the parent knot and updates the child 
+    /// position accordingly. Returns the new position of the child knot.
+    pub fn update_pos(&mut self, parent_pos: (i32, i32)) -> (i32, i32) {
+        let (dx, dy) = Knot::calc_dxy(self.position, parent_pos);
+        self.position.0 += dx;
+        self.position.1 += dy;
+        (self.position.0, self.position.1)
+    }
+}
+
+/// A rope of any number of knots (at least one). The first knot is the head, the last the tail.
+#[derive(Debug)]
+pub struct Rope {
+    knots: Vec<Knot>,
+    /// One set per knot of every location it has been, including the start.
+    visited_locations: Vec<HashSet<(i32, i32)>>,
+}
+
+impl Rope {
+    pub fn new(num_knots: usize) -> Self {
+        assert!(num_knots > 0, "a rope needs at least one knot");
+        let mut hs: HashSet<(i32, i32)> = HashSet::new();
+        hs.insert((0,0));
+        Self {
+            knots: vec![Knot::new(); num_knots],
+            visited_locations: vec![hs; num_knots],
+        }
+    }
+
+    fn parse_line(s: String) -> ((i32, i32), usize) {
+        let mut s = s.split_whitespace();
+        let direction = s.next().unwrap();
+        let mut chars = direction.chars();
+        let delta = match (chars.next(), chars.next()) {
+            (Some(c), None) => MovH::try_from(c).unwrap_or_else(|e| panic!("{e}")).delta(),
+            _ => panic!("Problem parsing {direction} as direction (expect U, L, R, or D)"),
+        };
+        let num_steps = s.last().unwrap().parse::<usize>().unwrap();
+        (delta, num_steps)
+    }
+
+    fn iter_step(&mut self, delta: (i32, i32)) -> (i32, i32) {
+        let mut position = (0,0);
+        for (i, knot) in &mut self.knots.iter_mut().enumerate() {
+            if i == 0 {
+                position = knot.update_rel(delta);
+            } else {
+                position = knot.update_pos(position);
+            }
+            self.visited_locations[i].insert(position);
+        }
+        position
+    }
+
+    /// Executes one line of the puzzle input, e.g. "R 4".
+    pub fn step(&mut self, s: String) {
+        self.step_with(s, |_| {});
+    }
+
+    /// Like `step`, but calls `on_step` after every single move of the head, e.g. to dump
+    /// a frame per step for an animation.
+    pub fn step_with<F: FnMut(&Self)>(&mut self, s: String, on_step: F) {
+        let (delta, num_steps) = Self::parse_line(s);
+        self.move_by_with(delta, num_steps, on_step);
+    }
+
+    /// Moves the head `num_steps` times by `delta`, which must be one of the four unit steps.
+    pub fn move_by(&mut self, delta: (i32, i32), num_steps: usize) {
+        self.move_by_with(delta, num_steps, |_| {});
+    }
+
+    fn move_by_with<F: FnMut(&Self)>(&mut self, delta: (i32, i32), num_steps: usize, mut on_step: F) {
+        for _ in 0..num_steps {
+            self.iter_step(delta);
+            on_step(self);
+        }
+    }
+
+    pub fn num_knots(&self) -> usize {
+        self.knots.len()
+    }
+
+    /// Current position of every knot, head first.
+    pub fn positions(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.knots.iter().map(|knot| knot.position)
+    }
+
+    pub fn head(&self) -> (i32, i32) {
+        self.knots[0].position
+    }
+
+    pub fn tail(&self) -> (i32, i32) {
+        self.knots[self.knots.len() - 1].position
+    }
+
+    /// Every location the tail has been, including the start.
+    pub fn visited(&self) -> &HashSet<(i32, i32)> {
+        self.visited_by(self.knots.len() - 1)
+    }
+
+    /// Every location knot `knot` (0 is the head) has been, including the start.
+    pub fn visited_by(&self, knot: usize) -> &HashSet<(i32, i32)> {
+        &self.visited_locations[knot]
+    }
+
+    pub fn size(&self) -> usize {
+        self.visited().len()
+    }
+}
+
+#[cfg(test)]
+mod rope_tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    #[allow(deprecated)]
+    use super::super::StateMachine;
+    use super::super::structs_9_test::{run, EXAMPLE, LARGER_EXAMPLE};
+
+    #[test]
+    fn calc_dxy() {
+        assert_eq!(Knot::calc_dxy((0,0), (0,0)), (0,0));
+        assert_eq!(Knot::calc_dxy((0,0), (1,0)), (0,0));
+        assert_eq!(Knot::calc_dxy((0,0), (1,1)), (0,0));
+        assert_eq!(Knot::calc_dxy((0,0), (0,1)), (0,0));
+        assert_eq!(Knot::calc_dxy((0,0), (-1,0)), (0,0));
+        assert_eq!(Knot::calc_dxy((0,0), (-1,-1)), (0,0));
+        assert_eq!(Knot::calc_dxy((0,0), (0,-1)), (0,0));
+        assert_eq!(Knot::calc_dxy((0,0), (1,-1)), (0,0));
+        assert_eq!(Knot::calc_dxy((0,0), (-1,1)), (0,0));
+
+        assert_eq!(Knot::calc_dxy((0,0), (2,0)), (1,0));
+        assert_eq!(Knot::calc_dxy((0,0), (2,1)), (1,1));
+        assert_eq!(Knot::calc_dxy((0,0), (2,2)), (1,1));
+        assert_eq!(Knot::calc_dxy((0,0), (1,2)), (1,1));
+        assert_eq!(Knot::calc_dxy((0,0), (0,2)), (0,1));
+        assert_eq!(Knot::calc_dxy((0,0), (-1,2)), (-1,1));
+        assert_eq!(Knot::calc_dxy((0,0), (-2,2)), (-1,1));
+        assert_eq!(Knot::calc_dxy((0,0), (-2,1)), (-1,1));
+        assert_eq!(Knot::calc_dxy((0,0), (-2,0)), (-1,0));
+        assert_eq!(Knot::calc_dxy((0,0), (-2,-1)), (-1,-1));
+        assert_eq!(Knot::calc_dxy((0,0), (-2,-2)), (-1,-1));
+        assert_eq!(Knot::calc_dxy((0,0), (-1,-2)), (-1,-1));
+        assert_eq!(Knot::calc_dxy((0,0), (0,-2)), (0,-1));
+        assert_eq!(Knot::calc_dxy((0,0), (1,-2)), (1,-1));
+        assert_eq!(Knot::calc_dxy((0,0), (2,-2)), (1,-1));
+        assert_eq!(Knot::calc_dxy((0,0), (2,-1)), (1,-1));
+    }
+
+    #[test]
+    fn test_update_pos() {
+        let mut knot = Knot {
+            position: (0,0)
+        };
+        knot.update_pos((1,1));
+        assert_eq!(knot.position, (0,0));
+        knot.update_pos((2,2));
+        assert_eq!(knot.position, (1,1));
+    }
+
+    #[test]
+    fn test_rel_update() {
+        let mut knot = Knot::new();
+        assert_eq!(knot.position, (0,0));
+        knot.update_rel((1,0));
+        assert_eq!(knot.position, (1,0));
+        knot.update_rel((1,0));
+        assert_eq!(knot.position, (2,0));
+        knot.update_rel((-1,0));
+        assert_eq!(knot.position, (1,0));
+        knot.update_rel((0,-1));
+        assert_eq!(knot.position, (1,-1));
+
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(Rope::parse_line("U 13".to_string()), ((0,1), 13));
+        assert_eq!(Rope::parse_line("D 13".to_string()), ((0,-1), 13));
+        assert_eq!(Rope::parse_line("L 13".to_string()), ((-1,0), 13));
+        assert_eq!(Rope::parse_line("R 13".to_string()), ((1,0), 13));
+    }
+
+    #[test]
+    fn test_iter_step() {
+        let mut rope = Rope::new(2);
+        assert_eq!(rope.iter_step((1,0)), (0,0));
+        assert_eq!(rope.iter_step((1,0)), (1,0));
+        assert_eq!(rope.iter_step((0,1)), (1,0));
+        assert_eq!(rope.iter_step((0,1)), (2,1));
+    }
+
+    #[test]
+    fn test_step() {
+        let mut rope = Rope::new(2);
+        rope.step("U 4".to_string());
+        assert_eq!(rope.visited().len(), 4);
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(run(2, EXAMPLE).size(), 13);
+        assert_eq!(run(10, EXAMPLE).size(), 1);
+        assert_eq!(run(10, LARGER_EXAMPLE).size(), 36);
+        assert_eq!(run(1, EXAMPLE).size(), 21);
+    }
+
+    #[test]
+    fn test_head_and_tail() {
+        let rope = run(10, LARGER_EXAMPLE);
+        assert_eq!(rope.head(), (-11, 15));
+        assert_eq!(rope.tail(), (-11, 6));
+        assert!(rope.visited().contains(&(0, 0)));
+    }
+
+    #[test]
+    fn test_visited_by() {
+        let rope = run(10, LARGER_EXAMPLE);
+        let sizes: Vec<usize> = (0..rope.num_knots()).map(|i| rope.visited_by(i).len()).collect();
+        // In this example each knot cuts the corners of the one before it.
+        assert!(sizes.windows(2).all(|pair| pair[0] >= pair[1]), "{sizes:?}");
+        assert_eq!(sizes[0], run(1, LARGER_EXAMPLE).size());
+        assert_eq!(sizes[1], run(2, LARGER_EXAMPLE).size());
+        assert_eq!(sizes[9], 36);
+    }
+
+    #[test]
+    fn test_step_with() {
+        let mut rope = Rope::new(3);
+        let mut heads = Vec::new();
+        rope.step_with("R 3".to_string(), |rope| heads.push(rope.head()));
+        assert_eq!(heads, [(1, 0), (2, 0), (3, 0)]);
+        assert_eq!(rope.positions().collect::<Vec<_>>(), [(3, 0), (2, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn test_input() {
+        assert_eq!(run(2, &std::fs::read_to_string("inputs/9.inputs.txt").unwrap()).size(), 5683);
+        assert_eq!(run(10, &std::fs::read_to_string("inputs/9.inputs.txt").unwrap()).size(), 2372);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_matches_state_machine() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..200 {
+            let mut rope = Rope::new(2);
+            let mut state = StateMachine::new();
+            for _ in 0..rng.gen_range(0..50) {
+                let line = format!("{} {}", ["U", "D", "L", "R"][rng.gen_range(0..4)], rng.gen_range(1..6));
+                rope.step(line.clone());
+                state.execute_line(line);
+            }
+            let expected: HashSet<(i32, i32)> = state.visited.iter().map(|&(x, y)| (x as i32, y as i32)).collect();
+            assert_eq!(rope.visited(), &expected);
+        }
+    }
+}
@@ -1,9 +1,11 @@
+pub mod day_7;
 pub mod day_8_algorithms;
 pub mod day_9_structs;
 pub mod day_10;
 pub mod day_11;
 pub mod day_12;
 pub mod day_13;
+pub mod pathfinding;
 
 pub use std::{fs::File, io::{BufRead, BufReader, Lines}, path::PathBuf};
 pub fn lines<T: ToString>(path: T) -> Lines<BufReader<File>> {
@@ -0,0 +1,378 @@
+/// Key concepts:
+/// * Generic functions: the searches work on any node type that is `Copy + Eq + Hash + Ord`,
+///   and the graph itself is just a closure that lists a node's neighbors.
+/// * Breadth-first search, Dijkstra and A* all have the same shape: take the most promising
+///   node off the frontier, push its neighbors. Only the frontier changes
+///   (VecDeque for BFS, BinaryHeap ordered by cost for Dijkstra and A*).
+/// * BinaryHeap is a max-heap. Wrapping entries in std::cmp::Reverse pops the cheapest first.
+/// * Parent pointers: remember which node each node was reached from, then walk back from
+///   the goal to rebuild the route.
+use std::cmp::Reverse;
+use std::collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A route found by one of the searches. `nodes` runs from the start to the goal, both included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub cost: u32,
+    pub nodes: Vec<N>,
+}
+
+impl<N> Path<N> {
+    /// Number of moves along the path (one less than the number of nodes).
+    pub fn steps(&self) -> usize {
+        self.nodes.len().saturating_sub(1)
+    }
+}
+
+/// Anything that can be searched. Only `neighbors` is required; `cost` defaults to 1 per move
+/// and `heuristic` defaults to 0, which turns A* into Dijkstra.
+pub trait Graph {
+    type Node: Copy + Eq + Hash + Ord;
+
+    fn neighbors(&self, node: &Self::Node) -> Vec<Self::Node>;
+
+    fn cost(&self, _from: &Self::Node, _to: &Self::Node) -> u32 {
+        1
+    }
+
+    /// Must never overestimate the remaining cost, or A* may return a longer path.
+    fn heuristic(&self, _from: &Self::Node, _goal: &Self::Node) -> u32 {
+        0
+    }
+
+    fn successors(&self, node: &Self::Node) -> Vec<(Self::Node, u32)> {
+        self.neighbors(node)
+            .into_iter()
+            .map(|next| (next, self.cost(node, &next)))
+            .collect()
+    }
+
+    /// Fewest moves from `start` to `goal`, ignoring `cost`.
+    fn bfs(&self, start: Self::Node, goal: Self::Node) -> Option<Path<Self::Node>> {
+        bfs(start, |node| *node == goal, |node| self.neighbors(node))
+    }
+
+    fn dijkstra(&self, start: Self::Node, goal: Self::Node) -> Option<Path<Self::Node>> {
+        dijkstra(start, |node| *node == goal, |node| self.successors(node))
+    }
+
+    fn astar(&self, start: Self::Node, goal: Self::Node) -> Option<Path<Self::Node>> {
+        astar(start, |node| *node == goal, |node| self.successors(node), |node| self.heuristic(node, &goal))
+    }
+}
+
+/// Breadth-first search. Every move costs 1, so the first goal reached is the closest one.
+pub fn bfs<N, G, F, I>(start: N, mut is_goal: G, mut neighbors: F) -> Option<Path<N>>
+where
+    N: Copy + Eq + Hash,
+    G: FnMut(&N) -> bool,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut queue: VecDeque<N> = VecDeque::new();
+    parents.insert(start, start);
+    queue.push_back(start);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            let nodes = reconstruct(&parents, node);
+            return Some(Path { cost: (nodes.len() - 1) as u32, nodes });
+        }
+        for next in neighbors(&node) {
+            if let Entry::Vacant(entry) = parents.entry(next) {
+                entry.insert(node);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Breadth-first search from both ends at once. `neighbors` moves forward from `start`,
+/// `rev_neighbors` lists the nodes that can move *into* a node, so the goal side walks backwards.
+/// Each round expands whichever frontier is smaller by one whole level. On long routes the two
+/// searches meet in the middle after visiting far fewer nodes than a single BFS.
+pub fn bidirectional_bfs<N, F, I, R, J>(start: N, goal: N, mut neighbors: F, mut rev_neighbors: R) -> Option<Path<N>>
+where
+    N: Copy + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    R: FnMut(&N) -> J,
+    J: IntoIterator<Item = N>,
+{
+    if start == goal {
+        return Some(Path { cost: 0, nodes: vec![start] });
+    }
+
+    // node -> (next node towards start/goal, distance from start/goal)
+    let mut forward: HashMap<N, (N, u32)> = HashMap::from([(start, (start, 0))]);
+    let mut backward: HashMap<N, (N, u32)> = HashMap::from([(goal, (goal, 0))]);
+    let mut forward_frontier = vec![start];
+    let mut backward_frontier = vec![goal];
+
+    while !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+        let meeting = if forward_frontier.len() <= backward_frontier.len() {
+            expand_level(&mut forward_frontier, &mut forward, &backward, &mut neighbors)
+        } else {
+            expand_level(&mut backward_frontier, &mut backward, &forward, &mut rev_neighbors)
+        };
+
+        if let Some(meeting) = meeting {
+            let mut nodes = reconstruct_from(&forward, meeting);
+            nodes.reverse();
+            nodes.extend(reconstruct_from(&backward, meeting).into_iter().skip(1));
+            return Some(Path { cost: (nodes.len() - 1) as u32, nodes });
+        }
+    }
+    None
+}
+
+/// Replaces `frontier` with the next BFS level. Returns the cheapest node where this search
+/// touched the `other` one, if any. The whole level is expanded before deciding, because the
+/// first meeting found isn't necessarily the one on the shortest path.
+fn expand_level<N, F, I>(frontier: &mut Vec<N>, visited: &mut HashMap<N, (N, u32)>, other: &HashMap<N, (N, u32)>, neighbors: &mut F) -> Option<N>
+where
+    N: Copy + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut next_frontier = Vec::new();
+    let mut best: Option<(u32, N)> = None;
+
+    for node in frontier.iter() {
+        let distance = visited[node].1 + 1;
+        for next in neighbors(node) {
+            if let Entry::Vacant(entry) = visited.entry(next) {
+                entry.insert((*node, distance));
+                next_frontier.push(next);
+                if let Some(&(_, remaining)) = other.get(&next) {
+                    if best.is_none_or(|(cost, _)| distance + remaining < cost) {
+                        best = Some((distance + remaining, next));
+                    }
+                }
+            }
+        }
+    }
+
+    *frontier = next_frontier;
+    best.map(|(_, node)| node)
+}
+
+/// Follows the links in a bidirectional search map from `node` back to the search's origin.
+fn reconstruct_from<N: Copy + Eq + Hash>(links: &HashMap<N, (N, u32)>, node: N) -> Vec<N> {
+    let mut nodes = vec![node];
+    let mut node = node;
+    while links[&node].0 != node {
+        node = links[&node].0;
+        nodes.push(node);
+    }
+    nodes
+}
+
+/// Breadth-first search that never stops early: the number of moves from `start` to every
+/// node it can reach.
+pub fn distances<N, F, I>(start: N, mut neighbors: F) -> HashMap<N, u32>
+where
+    N: Copy + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances: HashMap<N, u32> = HashMap::new();
+    let mut queue: VecDeque<N> = VecDeque::new();
+    distances.insert(start, 0);
+    queue.push_back(start);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node] + 1;
+        for next in neighbors(&node) {
+            if let Entry::Vacant(entry) = distances.entry(next) {
+                entry.insert(distance);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// Dijkstra's algorithm: cheapest path when moves have different (non-negative) costs.
+pub fn dijkstra<N, G, F, I>(start: N, is_goal: G, successors: F) -> Option<Path<N>>
+where
+    N: Copy + Eq + Hash + Ord,
+    G: FnMut(&N) -> bool,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u32)>,
+{
+    astar(start, is_goal, successors, |_| 0)
+}
+
+/// A* search. `heuristic` estimates the remaining cost to the goal and must not overestimate it.
+pub fn astar<N, G, F, I, H>(start: N, mut is_goal: G, mut successors: F, mut heuristic: H) -> Option<Path<N>>
+where
+    N: Copy + Eq + Hash + Ord,
+    G: FnMut(&N) -> bool,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u32)>,
+    H: FnMut(&N) -> u32,
+{
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut costs: HashMap<N, u32> = HashMap::new();
+    let mut heap: BinaryHeap<Reverse<(u32, u32, N)>> = BinaryHeap::new();   // (estimate, cost so far, node)
+    parents.insert(start, start);
+    costs.insert(start, 0);
+    heap.push(Reverse((heuristic(&start), 0, start)));
+
+    while let Some(Reverse((_, cost, node))) = heap.pop() {
+        if cost > costs[&node] {
+            continue;       // A cheaper route to this node was already expanded
+        }
+        if is_goal(&node) {
+            return Some(Path { cost, nodes: reconstruct(&parents, node) });
+        }
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_none_or(|&known| next_cost < known) {
+                costs.insert(next, next_cost);
+                parents.insert(next, node);
+                heap.push(Reverse((next_cost + heuristic(&next), next_cost, next)));
+            }
+        }
+    }
+    None
+}
+
+/// Manhattan (taxicab) distance between two grid coordinates.
+pub fn manhattan(a: &(usize, usize), b: &(usize, usize)) -> u32 {
+    (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as u32
+}
+
+/// Walks parent pointers back from `end` until reaching the node that is its own parent (the start).
+fn reconstruct<N: Copy + Eq + Hash>(parents: &HashMap<N, N>, end: N) -> Vec<N> {
+    let mut nodes = vec![end];
+    let mut node = end;
+    while parents[&node] != node {
+        node = parents[&node];
+        nodes.push(node);
+    }
+    nodes.reverse();
+    nodes
+}
+
+#[cfg(test)]
+mod pathfinding_tests {
+    use super::*;
+
+    /// '#' is a wall, everything else is open.
+    struct Maze(Vec<Vec<char>>);
+
+    impl Maze {
+        fn new(s: &str) -> Self {
+            Self(s.lines().map(|line| line.chars().collect()).collect())
+        }
+    }
+
+    impl Graph for Maze {
+        type Node = (usize, usize);
+
+        fn neighbors(&self, &(row, column): &(usize, usize)) -> Vec<(usize, usize)> {
+            let mut v = Vec::new();
+            if row > 0 { v.push((row - 1, column)) }
+            if row < self.0.len() - 1 { v.push((row + 1, column)) }
+            if column > 0 { v.push((row, column - 1)) }
+            if column < self.0[0].len() - 1 { v.push((row, column + 1)) }
+            v.retain(|&(r, c)| self.0[r][c] != '#');
+            v
+        }
+
+        /// Digits are slow terrain: entering one costs its value.
+        fn cost(&self, _from: &(usize, usize), &(row, column): &(usize, usize)) -> u32 {
+            self.0[row][column].to_digit(10).unwrap_or(1)
+        }
+
+        fn heuristic(&self, from: &(usize, usize), goal: &(usize, usize)) -> u32 {
+            manhattan(from, goal)
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = Maze::new("....\n.##.\n...#\n#...");
+        let path = maze.bfs((0, 0), (3, 3)).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(path.steps(), 6);
+        assert_eq!(path.nodes.first(), Some(&(0, 0)));
+        assert_eq!(path.nodes.last(), Some(&(3, 3)));
+        for pair in path.nodes.windows(2) {
+            assert_eq!(manhattan(&pair[0], &pair[1]), 1);
+        }
+
+        assert_eq!(maze.bfs((0, 0), (0, 0)).unwrap().nodes, vec![(0, 0)]);
+        assert_eq!(maze.bfs((0, 0), (1, 1)), None);
+    }
+
+    #[test]
+    fn test_weighted() {
+        // The short way through the middle crosses a 9, the long way around costs 1 per move.
+        let maze = Maze::new("...\n.9.\n.9.\n...");
+        assert_eq!(maze.bfs((1, 0), (1, 2)).unwrap().cost, 2);
+        assert_eq!(maze.dijkstra((1, 0), (1, 2)).unwrap().cost, 4);
+        assert_eq!(maze.astar((1, 0), (1, 2)).unwrap().cost, 4);
+        assert_eq!(maze.dijkstra((1, 0), (1, 2)).unwrap().nodes, vec![(1, 0), (0, 0), (0, 1), (0, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn test_bidirectional_bfs() {
+        let maze = Maze::new("....#.\n.##.#.\n...#..\n#.....\n.#.##.");
+        let neighbors = |node: &(usize, usize)| maze.neighbors(node);
+        for start in [(0, 0), (2, 2), (4, 0), (0, 5)] {
+            for goal in [(0, 0), (0, 5), (4, 5), (4, 2), (2, 4)] {
+                let expected = maze.bfs(start, goal);
+                let path = bidirectional_bfs(start, goal, neighbors, neighbors);
+                assert_eq!(path.as_ref().map(|p| p.cost), expected.map(|p| p.cost), "{start:?} -> {goal:?}");
+                if let Some(path) = path {
+                    assert_eq!(path.nodes.first(), Some(&start));
+                    assert_eq!(path.nodes.last(), Some(&goal));
+                    assert_eq!(path.steps() as u32, path.cost);
+                    for pair in path.nodes.windows(2) {
+                        assert_eq!(manhattan(&pair[0], &pair[1]), 1);
+                    }
+                }
+            }
+        }
+        // (4, 0) is walled in.
+        assert_eq!(bidirectional_bfs((0, 0), (4, 0), neighbors, neighbors), None);
+    }
+
+    #[test]
+    fn test_bidirectional_one_way() {
+        // Edges only go up: n -> n + 1 and n -> n + 3.
+        let path = bidirectional_bfs(0_i32, 10, |&n| [n + 1, n + 3], |&n| [n - 1, n - 3]).unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes.first(), Some(&0));
+        assert_eq!(path.nodes.last(), Some(&10));
+        assert_eq!(bidirectional_bfs(10_i32, 0, |&n| [n + 1, n + 3].into_iter().filter(|&m| m < 20), |&n| [n - 1, n - 3].into_iter().filter(|&m| m > 0)), None);
+    }
+
+    #[test]
+    fn test_distances() {
+        let maze = Maze::new("..#\n#..\n#.#");
+        let distances = distances((0, 0), |node| maze.neighbors(node));
+        assert_eq!(distances.len(), 5);
+        assert_eq!(distances[&(0, 0)], 0);
+        assert_eq!(distances[&(1, 2)], 3);
+        assert_eq!(distances[&(2, 1)], 3);
+        assert!(!distances.contains_key(&(0, 2)));
+    }
+
+    #[test]
+    fn test_closures() {
+        // Collatz-style graph: no grid at all.
+        let path = bfs(27_u64, |&n| n == 1, |&n| if n % 2 == 0 { vec![n / 2] } else { vec![3 * n + 1] }).unwrap();
+        assert_eq!(path.steps(), 111);
+
+        let path = dijkstra(0_i32, |&n| n == 10, |&n| [(n + 1, 3), (n + 5, 10)]).unwrap();
+        assert_eq!(path.cost, 20);
+        assert_eq!(path.nodes, vec![0, 5, 10]);
+    }
+}
@@ -0,0 +1,55 @@
+//! Materializes the reconstructed day 7 filesystem on disk and checks every folder size
+//! against `du`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use advent_of_code::day_7::Filesystem;
+
+#[test]
+fn sizes_match_du() {
+    let fs = Filesystem::read("inputs/7.inputs.txt");
+    let dir = std::env::temp_dir().join(format!("advent_of_code_day_7_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    fs.materialize(&dir).unwrap();
+
+    // `du -b` prints the apparent size of every directory below `dir`, one per line.
+    // Needs GNU du; BSD/macOS du has no -b.
+    let output = Command::new("du").arg("-b").arg(&dir).output()
+        .expect("this test needs GNU `du` on PATH");
+    assert!(
+        output.status.success(),
+        "`du -b` failed (this test needs GNU du): {}",
+        String::from_utf8_lossy(&output.stderr),
+    );
+    let du: HashMap<PathBuf, u64> = String::from_utf8(output.stdout).unwrap()
+        .lines()
+        .map(|line| {
+            let (size, path) = line.split_once('\t').unwrap();
+            (PathBuf::from(path), size.parse().unwrap())
+        })
+        .collect();
+
+    // du also counts the directory entries themselves, so add their sizes to ours.
+    let folders = fs.preorder();
+    let path = |name: &str| dir.join(name.trim_start_matches('/'));
+    let own_size: HashMap<String, u64> = folders.iter()
+        .map(|(f, _)| fs.qualified_name(f))
+        .map(|name| { let size = std::fs::metadata(path(&name)).unwrap().len(); (name, size) })
+        .collect();
+
+    assert_eq!(du.len(), folders.len());
+    for (folder, _) in &folders {
+        let name = fs.qualified_name(folder);
+        let name = name.as_str();
+        let overhead: u64 = own_size.iter()
+            .filter(|(other, _)| other.starts_with(name))
+            .map(|(_, size)| size)
+            .sum();
+        let expected = folder.size().unwrap() + overhead;
+        assert_eq!(du[&path(name).components().collect::<PathBuf>()], expected, "{name}");
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}